@@ -0,0 +1,103 @@
+/*
+
+    Core Hot Path Benchmarks
+
+    Run with: cargo bench
+    No extra crates -> each case is timed over a number of samples and the median is reported
+
+    decode       : Opcode -> Instruction table lookup for every opcode
+    alu_loop     : Register ALU loop running from WRAM (instruction dispatch + flags)
+    cpu_instrs   : Recorded workload, the first frames of blargg's cpu_instrs ROM
+
+    PPU scanline rendering and APU mixing get cases here once they exist
+
+*/
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use gameboy::hdw::bus::Bus;
+use gameboy::hdw::cart::Cartridge;
+use gameboy::hdw::cpu::CPU;
+use gameboy::hdw::emu::CYCLES_PER_FRAME;
+use gameboy::hdw::instructions::Instruction;
+use gameboy::hdw::state::{Savestate, StateReader, StateWriter};
+use gameboy::hdw::test_util::cpu_with_program;
+
+const SAMPLES: usize = 15;
+
+// Function to time a case and print the median sample
+fn bench(name: &str, iterations: u64, mut case: impl FnMut()) {
+    // Warm up
+    case();
+
+    let mut samples: Vec<Duration> = (0..SAMPLES)
+        .map(|_| {
+            let start = Instant::now();
+            case();
+            start.elapsed()
+        })
+        .collect();
+    samples.sort();
+
+    let median = samples[SAMPLES / 2];
+    println!(
+        "{:<12} {:>10.3} ms   {:>8.1} ns/iter   (min {:.3} ms, max {:.3} ms)",
+        name,
+        median.as_secs_f64() * 1e3,
+        median.as_nanos() as f64 / iterations as f64,
+        samples[0].as_secs_f64() * 1e3,
+        samples[SAMPLES - 1].as_secs_f64() * 1e3
+    );
+}
+
+fn main() {
+    // Decode
+    let bus = cpu_with_program(&[0xCB, 0x37]).bus;
+    bench("decode", 256 * 100, || {
+        for _ in 0..100 {
+            for opcode in 0..=0xFF {
+                black_box(Instruction::decode_from_opcode(
+                    black_box(opcode),
+                    &bus,
+                    0xC000,
+                ));
+            }
+        }
+    });
+
+    // ALU loop -> ADD A,B / XOR C / INC D / RLC E / DEC B / JR back to the start
+    let alu_steps = 100_000;
+    bench("alu_loop", alu_steps, || {
+        let mut cpu = cpu_with_program(&[0x80, 0xA9, 0x14, 0xCB, 0x03, 0x05, 0x18, 0xF8]);
+        for ticks in 0..alu_steps {
+            cpu.step(ticks).unwrap();
+        }
+        black_box(cpu.registers.a);
+    });
+
+    // Recorded workload, rewound with a save state between samples
+    let frames = 60;
+    let mut cart = Cartridge::new();
+    match cart.load_cart(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../src/roms/cpu_instrs.gb"
+    )) {
+        Ok(()) => {
+            let mut cpu = CPU::new(Bus::new(cart));
+            let mut writer = StateWriter::new();
+            cpu.save_state(&mut writer);
+            let power_on = writer.finish();
+
+            bench("cpu_instrs", frames, || {
+                cpu.load_state(&mut StateReader::new(&power_on)).unwrap();
+                let mut ticks = 0;
+                while cpu.cycles < frames * CYCLES_PER_FRAME {
+                    cpu.step(ticks).unwrap();
+                    ticks += 1;
+                }
+                black_box(cpu.pc);
+            });
+        }
+        Err(e) => println!("cpu_instrs   skipped: {}", e),
+    }
+}
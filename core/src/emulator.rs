@@ -0,0 +1,192 @@
+/*
+    Emulator facade - the stable way to drive the core without knowing the hdw modules
+*/
+use crate::hdw::bus::Bus;
+use crate::hdw::cart::Cartridge;
+use crate::hdw::emu::EmuContext;
+use crate::hdw::joypad::Button;
+use crate::hdw::model::Model;
+
+/// A complete Game Boy with a cartridge inserted.
+///
+/// ```
+/// use gameboy::{Button, Emulator};
+/// # fn test_rom() -> Vec<u8> {
+/// #     let mut rom = vec![0; 0x8000];
+/// #     rom[0x100..0x103].copy_from_slice(&[0x3C, 0x18, 0xFD]); // INC A ; JR -3
+/// #     rom[0x14D] = rom[0x134..0x14D].iter().fold(0u8, |c, b| c.wrapping_sub(*b).wrapping_sub(1));
+/// #     rom
+/// # }
+///
+/// let mut emulator = Emulator::from_rom_bytes("demo.gb", test_rom())?;
+/// emulator.set_button(Button::Start, true);
+/// for _ in 0..60 {
+///     emulator.frame()?;
+/// }
+/// assert_eq!(emulator.frame_count(), 60);
+/// # Ok::<(), String>(())
+/// ```
+pub struct Emulator {
+    ctx: EmuContext,
+}
+
+impl Emulator {
+    /// Loads a ROM file (applying any `.ips`/`.bps` patch next to it) and powers on.
+    pub fn load_rom(path: &str) -> Result<Emulator, String> {
+        let mut cart = Cartridge::new();
+        cart.load_cart(path)?;
+        Ok(Emulator::with_cart(cart))
+    }
+
+    /// Powers on with ROM contents already in memory, `name` is only used in reports.
+    ///
+    /// Fails if the header is truncated, its checksum is wrong or the ROM is smaller than
+    /// the header says.
+    pub fn from_rom_bytes(name: &str, data: Vec<u8>) -> Result<Emulator, String> {
+        let mut cart = Cartridge::new();
+        cart.load_data(name, data)?;
+        Ok(Emulator::with_cart(cart))
+    }
+
+    fn with_cart(cart: Cartridge) -> Emulator {
+        Emulator {
+            ctx: EmuContext::new(Bus::new(cart)),
+        }
+    }
+
+    /// Runs a single instruction (or one idle M-cycle while halted/stopped).
+    ///
+    /// Returns the error that stopped the CPU, such as an illegal opcode.
+    pub fn step(&mut self) -> Result<(), String> {
+        self.ctx.execute_cpu_step();
+        self.check_error()
+    }
+
+    /// Runs exactly one frame worth of cycles (70224 T-cycles).
+    pub fn frame(&mut self) -> Result<(), String> {
+        self.ctx.run_frame();
+        self.check_error()
+    }
+
+    fn check_error(&mut self) -> Result<(), String> {
+        match self.ctx.take_error() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Number of whole frames run since power on.
+    pub fn frame_count(&self) -> u64 {
+        self.ctx.frames
+    }
+
+    /// Presses or releases a button, raising the joypad interrupt when the game is listening.
+    pub fn set_button(&mut self, button: Button, pressed: bool) {
+        self.ctx.set_button(button, pressed);
+    }
+
+    /// Switches the machine model (DMG by default) and power cycles so games see its boot state.
+    pub fn set_model(&mut self, model: Model) {
+        self.ctx.set_model(model);
+    }
+
+    /// Power cycles the machine, cartridge RAM is kept.
+    pub fn reset(&mut self) {
+        self.ctx.reset();
+    }
+
+    /// Re-reads the ROM file from disk and power cycles, for edit-build-run loops.
+    ///
+    /// With `keep_ram` the cartridge RAM carries over when the new build has the same size.
+    pub fn reload_rom(&mut self, keep_ram: bool) -> Result<(), String> {
+        self.ctx.reload_rom(keep_ram)
+    }
+
+    /// Serializes the whole machine.
+    ///
+    /// ```
+    /// # use gameboy::Emulator;
+    /// # fn test_rom() -> Vec<u8> {
+    /// #     let mut rom = vec![0; 0x8000];
+    /// #     rom[0x100..0x103].copy_from_slice(&[0x3C, 0x18, 0xFD]); // INC A ; JR -3
+    /// #     rom[0x14D] = rom[0x134..0x14D].iter().fold(0u8, |c, b| c.wrapping_sub(*b).wrapping_sub(1));
+    /// #     rom
+    /// # }
+    /// let mut emulator = Emulator::from_rom_bytes("demo.gb", test_rom())?;
+    /// emulator.write_memory(0xC000, &[0x42]);
+    /// let state = emulator.save_state();
+    ///
+    /// emulator.write_memory(0xC000, &[0x00]);
+    /// emulator.load_state(&state)?;
+    /// assert_eq!(emulator.read_memory(0xC000, 1), [0x42]);
+    /// # Ok::<(), String>(())
+    /// ```
+    pub fn save_state(&self) -> Vec<u8> {
+        self.ctx.save_state()
+    }
+
+    /// Restores a [`save_state`](Emulator::save_state), a bad state leaves the machine as it was.
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), String> {
+        self.ctx.load_state(data)
+    }
+
+    /// Reads memory as the CPU sees it without side effects.
+    pub fn read_memory(&self, address: u16, length: usize) -> Vec<u8> {
+        self.ctx.read_memory(address, length)
+    }
+
+    /// Writes memory as the CPU would (writes to ROM addresses go to the mapper).
+    pub fn write_memory(&mut self, address: u16, data: &[u8]) {
+        self.ctx.write_memory(address, data);
+    }
+
+    /// Reads an IO register by its Pan Docs name (`LCDC`, `STAT`, `NR52`...), ignoring case.
+    ///
+    /// Unused bits read as 1 like on hardware. Returns `None` for an unknown name.
+    ///
+    /// ```
+    /// # use gameboy::Emulator;
+    /// # fn test_rom() -> Vec<u8> {
+    /// #     let mut rom = vec![0; 0x8000];
+    /// #     rom[0x100..0x103].copy_from_slice(&[0x3C, 0x18, 0xFD]); // INC A ; JR -3
+    /// #     rom[0x14D] = rom[0x134..0x14D].iter().fold(0u8, |c, b| c.wrapping_sub(*b).wrapping_sub(1));
+    /// #     rom
+    /// # }
+    /// let mut emulator = Emulator::from_rom_bytes("demo.gb", test_rom())?;
+    /// emulator.set_io_register("SCX", 0x20)?;
+    /// assert_eq!(emulator.io_register("scx"), Some(0x20));
+    /// assert_eq!(emulator.io_register("SCZ"), None);
+    /// # Ok::<(), String>(())
+    /// ```
+    pub fn io_register(&self, name: &str) -> Option<u8> {
+        self.ctx.io_register(name)
+    }
+
+    /// Writes an IO register by name as the CPU would, so write rules such as NR52's apply.
+    ///
+    /// ```
+    /// # use gameboy::Emulator;
+    /// # fn test_rom() -> Vec<u8> {
+    /// #     let mut rom = vec![0; 0x8000];
+    /// #     rom[0x100..0x103].copy_from_slice(&[0x3C, 0x18, 0xFD]); // INC A ; JR -3
+    /// #     rom[0x14D] = rom[0x134..0x14D].iter().fold(0u8, |c, b| c.wrapping_sub(*b).wrapping_sub(1));
+    /// #     rom
+    /// # }
+    /// let mut emulator = Emulator::from_rom_bytes("demo.gb", test_rom())?;
+    /// // Only the power bit of NR52 is writable
+    /// emulator.set_io_register("NR52", 0x8F)?;
+    /// assert_eq!(emulator.io_register("NR52"), Some(0xF0));
+    /// assert!(emulator.set_io_register("SCZ", 0x00).is_err());
+    /// # Ok::<(), String>(())
+    /// ```
+    pub fn set_io_register(&mut self, name: &str, value: u8) -> Result<(), String> {
+        self.ctx.set_io_register(name, value)
+    }
+
+    /// Takes the bytes the game has sent over the serial port since the last call.
+    ///
+    /// Test ROMs such as blargg's print their results this way.
+    pub fn take_serial_output(&mut self) -> Vec<u8> {
+        self.ctx.take_serial_output()
+    }
+}
@@ -0,0 +1,351 @@
+/*
+
+    Gameboy Memory Bus
+
+    Most of the function here is to reroute referencing of data
+    0x0000 - 0x3FFF : ROM Bank 0
+    0x4000 - 0x7FFF : ROM Bank 1 - Switchable
+    0x8000 - 0x97FF : CHR RAM
+    0x9800 - 0x9BFF : BG Map 1
+    0x9C00 - 0x9FFF : BG Map 2
+    0xA000 - 0xBFFF : Cartridge RAM
+    0xC000 - 0xCFFF : RAM Bank 0
+    0xD000 - 0xDFFF : RAM Bank 1-7 - switchable - Color only
+    0xE000 - 0xFDFF : Echo RAM - Mirrors 0xC000 - 0xDDFF
+    0xFE00 - 0xFE9F : Object Attribute Memory
+    0xFEA0 - 0xFEFF : Reserved - Unusable, reads 0x00 and ignores writes
+    0xFF00 - 0xFF7F : I/O Registers
+    0xFF80 - 0xFFFE : Zero Page
+    0xFFFF          : Interrupt Enable
+
+    IE and IF live here rather than on the CPU so nothing needs a CPU reference to reach them
+
+*/
+
+use log::{info, warn};
+use std::cell::Cell;
+
+use super::cart::Cartridge;
+use crate::hdw::cdl::CodeDataLog;
+use crate::hdw::debug::{WatchAccess, WatchAction, WatchHit, Watchpoint};
+use crate::hdw::events::{Event, EventLog};
+use crate::hdw::heatmap::{HeatAccess, Heatmap};
+use crate::hdw::interrupts::{request_interrupt, Interrupts};
+use crate::hdw::io::IO;
+use crate::hdw::joypad::{Button, Joypad};
+use crate::hdw::ram::RAM;
+use crate::hdw::serial::Serial;
+use crate::hdw::state::{Savestate, StateReader, StateWriter};
+use crate::hdw::timer::Timer;
+
+pub struct Bus {
+    pub cart: Cartridge,
+    ram: RAM,
+    io: IO,
+    joypad: Joypad,
+    pub serial: Serial,
+    pub timer: Timer,
+    pub ie_register: u8,
+    pub int_flags: u8,
+    pub watchpoints: Vec<Watchpoint>,
+    watch_hit: Cell<Option<WatchHit>>,
+    pub heatmap: Option<Heatmap>,
+    pub cdl: Option<CodeDataLog>,
+    pub events: Option<EventLog>,
+}
+
+impl Bus {
+    // Consructor
+    pub fn new(cart: Cartridge) -> Self {
+        Bus {
+            // initialize vars
+            cart,
+            ram: RAM::new(),
+            io: IO::new(),
+            joypad: Joypad::new(),
+            serial: Serial::new(),
+            timer: Timer::new(),
+            ie_register: 0,
+            int_flags: 0,
+            watchpoints: Vec::new(),
+            watch_hit: Cell::new(None),
+            heatmap: None,
+            cdl: None,
+            events: None,
+        }
+    }
+
+    // Function to return a byte at an address
+    pub fn read_byte(&self, address: u16) -> u8 {
+        let value = self.read_mapped(address);
+
+        // Only pay for watchpoints when some are set
+        if !self.watchpoints.is_empty() {
+            self.watch_check(address, value, WatchAccess::Read);
+        }
+        if let Some(heatmap) = &self.heatmap {
+            heatmap.record(address, HeatAccess::Read);
+        }
+        if let Some(cdl) = &self.cdl {
+            if let Some(offset) = self.cart.rom_offset(address) {
+                cdl.mark_data(offset);
+            }
+        }
+        value
+    }
+
+    // Function to read a byte without triggering watchpoints (tracing/debug views)
+    pub fn peek_byte(&self, address: u16) -> u8 {
+        self.read_mapped(address)
+    }
+
+    // Function to route a read to the correct place
+    fn read_mapped(&self, address: u16) -> u8 {
+        if address < 0x8000 {
+            // ROM DATA
+            let result = self.cart.read_byte(address);
+            result
+        } else if address < 0xA000 {
+            // Char/Map Data
+            warn!("MEM NOT IMPL: VRAM read {:04X}", address);
+            0
+        } else if address < 0xC000 {
+            // Cartridge RAM
+            self.cart.read_byte(address)
+        } else if address < 0xE000 {
+            // WRAM
+            self.ram.wram_read(address)
+        } else if address < 0xFE00 {
+            // Echo RAM
+            self.ram.wram_read(address - 0x2000)
+        } else if address < 0xFEA0 {
+            // OAM
+            warn!("MEM NOT IMPL: OAM read {:04X}", address);
+            0
+        } else if address < 0xFF00 {
+            // Reserved Unusable
+            0
+        } else if address == 0xFF00 {
+            // Joypad
+            self.joypad.read()
+        } else if address == 0xFF01 || address == 0xFF02 {
+            // Serial
+            self.serial.read(address)
+        } else if (0xFF04..=0xFF07).contains(&address) {
+            // Timer
+            self.timer.read(address)
+        } else if address == 0xFF0F {
+            // Interrupt Flags (top 3 bits unused)
+            self.int_flags | 0xE0
+        } else if address < 0xFF80 {
+            // IO Registers
+            self.io.io_read(address)
+        } else if address == 0xFFFF {
+            // Interrupt Enable
+            self.ie_register
+        } else {
+            // HRAM (Zero Page)
+            self.ram.hram_read(address)
+        }
+    }
+
+    // Function to write byte to correct place
+    pub fn write_byte(&mut self, address: u16, value: u8) {
+        // Only pay for watchpoints when some are set
+        if !self.watchpoints.is_empty() {
+            self.watch_check(address, value, WatchAccess::Write);
+        }
+        if let Some(heatmap) = &self.heatmap {
+            heatmap.record(address, HeatAccess::Write);
+        }
+
+        // Need to filter destination of byte and write to there
+        if address < 0x8000 {
+            // ROM DATA -> MBC registers
+            let banks = self.events.is_some().then(|| self.cart_banks());
+            self.cart.write_byte(address, value);
+            if let Some(banks) = banks {
+                self.log_bank_changes(banks);
+            }
+        } else if address < 0xA000 {
+            // Char/Map Data
+            warn!("MEM NOT IMPL: VRAM write {:04X}", address)
+        } else if address < 0xC000 {
+            // EXT RAM
+            self.cart.write_byte(address, value);
+        } else if address < 0xE000 {
+            // WRAM
+            self.ram.wram_write(address, value);
+        } else if address < 0xFE00 {
+            // Echo RAM
+            self.ram.wram_write(address - 0x2000, value);
+        } else if address < 0xFEA0 {
+            // OAM RAM
+            warn!("MEM NOT IMPL: OAM write {:04X}", address)
+        } else if address < 0xFF00 {
+            // Reserved Unusuable
+        } else if address == 0xFF00 {
+            // Joypad -> selecting a row with a button held can fire the interrupt
+            if self.joypad.write(value) {
+                request_interrupt(self, Interrupts::JOYPAD);
+            }
+        } else if address == 0xFF01 || address == 0xFF02 {
+            // Serial
+            self.serial.write(address, value);
+        } else if (0xFF04..=0xFF07).contains(&address) {
+            // Timer
+            self.timer.write(address, value);
+        } else if address == 0xFF0F {
+            // Interrupt Flags
+            self.int_flags = value & 0x1F;
+        } else if address < 0xFF80 {
+            // IO Registers
+            self.io.io_write(address, value);
+        } else if address == 0xFFFF {
+            // Interrupt Enable
+            self.ie_register = value;
+        } else {
+            // HRAM
+            self.ram.hram_write(address, value);
+        }
+    }
+
+    // Function to advance the hardware on the bus by some M-cycles
+    pub fn tick(&mut self, m_cycles: u8) {
+        if let Some(events) = &mut self.events {
+            events.advance(m_cycles);
+        }
+        if self.timer.tick(m_cycles) {
+            request_interrupt(self, Interrupts::TIMER);
+        }
+        if self.serial.tick(m_cycles) {
+            request_interrupt(self, Interrupts::SERIAL);
+        }
+    }
+
+    // Function to get the cart's (ROM bank, RAM bank, RAM enabled)
+    fn cart_banks(&self) -> (u8, u8, bool) {
+        (
+            self.cart.rom_bank(),
+            self.cart.ram_bank(),
+            self.cart.ram_enabled(),
+        )
+    }
+
+    // Function to log whatever an MBC write switched
+    fn log_bank_changes(&mut self, (rom_bank, ram_bank, ram_enabled): (u8, u8, bool)) {
+        let now = self.cart_banks();
+        if let Some(events) = &mut self.events {
+            if now.0 != rom_bank {
+                events.push(Event::RomBank(now.0));
+            }
+            if now.1 != ram_bank {
+                events.push(Event::RamBank(now.1));
+            }
+            if now.2 != ram_enabled {
+                events.push(Event::RamEnabled(now.2));
+            }
+        }
+    }
+
+    // Function to press or release a button
+    pub fn set_button(&mut self, button: Button, pressed: bool) {
+        if self.joypad.set_button(button, pressed) {
+            request_interrupt(self, Interrupts::JOYPAD);
+        }
+    }
+
+    // Function to check if a held button is pulling a selected joypad line low
+    pub fn joypad_line_low(&self) -> bool {
+        self.joypad.line_low()
+    }
+
+    // Function to log/flag any watchpoints covering an access
+    fn watch_check(&self, address: u16, value: u8, access: WatchAccess) {
+        for watchpoint in &self.watchpoints {
+            if watchpoint.matches(address, access) {
+                let hit = WatchHit {
+                    address,
+                    value,
+                    access,
+                };
+                info!("{}", hit);
+
+                if watchpoint.action == WatchAction::Break {
+                    self.watch_hit.set(Some(hit));
+                }
+            }
+        }
+    }
+
+    // Function to take the last breaking watchpoint hit if there was one
+    pub fn take_watch_hit(&self) -> Option<WatchHit> {
+        self.watch_hit.take()
+    }
+}
+
+// Debugger state (watchpoints) is left alone on load
+impl Savestate for Bus {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.section(b"BUS ");
+        writer.write_u8(self.ie_register);
+        writer.write_u8(self.int_flags);
+        self.ram.save_state(writer);
+        self.io.save_state(writer);
+        self.joypad.save_state(writer);
+        self.serial.save_state(writer);
+        self.timer.save_state(writer);
+        self.cart.save_state(writer);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        reader.section(b"BUS ")?;
+        self.ie_register = reader.read_u8()?;
+        self.int_flags = reader.read_u8()?;
+        self.ram.load_state(reader)?;
+        self.io.load_state(reader)?;
+        self.joypad.load_state(reader)?;
+        self.serial.load_state(reader)?;
+        self.timer.load_state(reader)?;
+        self.cart.load_state(reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdw::symbols::SymbolTable;
+
+    // Function to get a bus with watchpoints from specs
+    fn watched_bus(specs: &[&str]) -> Bus {
+        let mut bus = Bus::new(Cartridge::new());
+        for spec in specs {
+            let watchpoint = Watchpoint::parse(spec, &SymbolTable::new()).unwrap();
+            bus.watchpoints.push(watchpoint);
+        }
+        bus
+    }
+
+    #[test]
+    fn break_hits_are_taken_once() {
+        let mut bus = watched_bus(&["C000:w:break"]);
+        bus.write_byte(0xC000, 0x42);
+
+        let hit = bus.take_watch_hit().unwrap();
+        assert_eq!((hit.address, hit.value), (0xC000, 0x42));
+        assert_eq!(hit.access, WatchAccess::Write);
+        assert!(bus.take_watch_hit().is_none());
+
+        // Reads aren't watched
+        bus.read_byte(0xC000);
+        assert!(bus.take_watch_hit().is_none());
+    }
+
+    #[test]
+    fn log_hits_dont_break() {
+        let mut bus = watched_bus(&["C000-C0FF:rw:log"]);
+        bus.write_byte(0xC010, 0x42);
+        bus.read_byte(0xC010);
+        assert!(bus.take_watch_hit().is_none());
+    }
+}
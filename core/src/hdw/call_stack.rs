@@ -0,0 +1,230 @@
+/*
+
+    Shadow Call Stack
+
+    Follows CALL/RST/interrupt entries and RET/RETI exits alongside the real stack so the
+    debugger can print a backtrace without trusting what is in stack memory
+
+    Games that play with the stack (popping a return address to jump elsewhere, resetting SP
+    from the main loop) are handled by matching on SP instead of popping blindly:
+        A return drops every frame at or below the SP it returns from
+        An entry drops stale frames at or below the SP it pushes to
+
+    Pushes that land outside RAM (ROM, VRAM, echo RAM, OAM, IO, or A000-BFFF without enabled
+    cart RAM) are reported as a stack overflow once, until the stack is back in RAM
+
+    Backtraces list the current PC then the return address of each frame, innermost first
+
+*/
+use std::fmt::Write;
+
+use log::warn;
+
+use crate::hdw::symbols::SymbolTable;
+
+// Deepest stack kept, the oldest frames are dropped past this
+pub const MAX_DEPTH: usize = 256;
+
+// How a frame was entered
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CallKind {
+    Call,
+    Rst,
+    Interrupt,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CallFrame {
+    pub kind: CallKind,
+    pub site: u16,   // PC of the CALL/RST or the instruction an interrupt cut in before
+    pub target: u16, // Where it went
+    pub sp: u16,     // Where the return address was pushed
+    pub bank: u8,    // ROM bank mapped at 4000-7FFF at the time
+}
+
+impl CallFrame {
+    // Method to get where execution continues in the caller
+    pub fn return_address(&self) -> u16 {
+        match self.kind {
+            CallKind::Call => self.site.wrapping_add(3),
+            CallKind::Rst => self.site.wrapping_add(1),
+            // The PC pushed is the instruction that was cut in before
+            CallKind::Interrupt => self.site,
+        }
+    }
+}
+
+pub struct CallStack {
+    frames: Vec<CallFrame>,
+    overflowed: bool,
+}
+
+// Function to check if a stack push lands in RAM (cart RAM when mapped, WRAM or HRAM)
+pub fn is_stack_ram(address: u16, cart_ram: bool) -> bool {
+    match address {
+        0xA000..=0xBFFF => cart_ram,
+        0xC000..=0xDFFF | 0xFF80..=0xFFFE => true,
+        _ => false,
+    }
+}
+
+impl CallStack {
+    // Constructor
+    pub fn new() -> Self {
+        CallStack {
+            frames: Vec::new(),
+            overflowed: false,
+        }
+    }
+
+    // Method to record a CALL, RST or interrupt entry
+    pub fn enter(&mut self, frame: CallFrame) {
+        self.frames.retain(|other| other.sp > frame.sp);
+        if self.frames.len() == MAX_DEPTH {
+            self.frames.remove(0);
+        }
+        self.frames.push(frame);
+    }
+
+    // Method to record a RET/RETI taken with SP at sp
+    pub fn exit(&mut self, sp: u16) {
+        while self.frames.last().is_some_and(|frame| frame.sp <= sp) {
+            self.frames.pop();
+        }
+    }
+
+    // Method to check a stack push, warning the first time one leaves RAM
+    pub fn check_push(&mut self, address: u16, pc: u16, cart_ram: bool) {
+        if is_stack_ram(address, cart_ram) {
+            self.overflowed = false;
+        } else if !self.overflowed {
+            self.overflowed = true;
+            warn!(
+                "Stack overflow: push to {:04X} outside RAM at PC {:04X}",
+                address, pc
+            );
+        }
+    }
+
+    // Method to get the frames, innermost last
+    pub fn frames(&self) -> &[CallFrame] {
+        &self.frames
+    }
+
+    // Method to forget every frame (reset/state load)
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.overflowed = false;
+    }
+
+    // Method to format a backtrace starting from the current PC, innermost first
+    pub fn backtrace(&self, pc: u16, bank: u8, symbols: &SymbolTable) -> String {
+        let name = |address: u16, bank: u8| match symbols.label(address, bank) {
+            Some(label) => format!("{:04X} {}", address, label),
+            None => format!("{:04X}", address),
+        };
+
+        let mut backtrace = String::new();
+        // Writing to a String can't fail
+        let _ = writeln!(backtrace, "  #0  {}", name(pc, bank));
+        for (depth, frame) in self.frames.iter().rev().enumerate() {
+            let how = match frame.kind {
+                CallKind::Call => "call",
+                CallKind::Rst => "rst",
+                CallKind::Interrupt => "interrupt",
+            };
+            let _ = writeln!(
+                backtrace,
+                "  #{:<2} {}  (return from {} {})",
+                depth + 1,
+                name(frame.return_address(), frame.bank),
+                how,
+                name(frame.target, frame.bank)
+            );
+        }
+        backtrace
+    }
+}
+
+impl Default for CallStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(kind: CallKind, site: u16, target: u16, sp: u16) -> CallFrame {
+        CallFrame {
+            kind,
+            site,
+            target,
+            sp,
+            bank: 1,
+        }
+    }
+
+    #[test]
+    fn tracks_calls_and_tolerates_stack_tricks() {
+        let mut stack = CallStack::new();
+        stack.enter(frame(CallKind::Call, 0x0150, 0x0200, 0xDFFC));
+        stack.enter(frame(CallKind::Rst, 0x0210, 0x0038, 0xDFFA));
+        stack.enter(frame(CallKind::Interrupt, 0x0040, 0x0040, 0xDFF8));
+        assert_eq!(stack.frames().len(), 3);
+
+        // RETI from the interrupt
+        stack.exit(0xDFF8);
+        assert_eq!(stack.frames().len(), 2);
+
+        // The RST handler pops its return address and the caller returns instead
+        stack.exit(0xDFFC);
+        assert!(stack.frames().is_empty());
+
+        // Main loop resets SP and calls again -> the old frames are stale
+        stack.enter(frame(CallKind::Call, 0x0150, 0x0200, 0xDFF0));
+        stack.enter(frame(CallKind::Call, 0x0160, 0x0300, 0xDFFC));
+        assert_eq!(
+            stack.frames(),
+            [frame(CallKind::Call, 0x0160, 0x0300, 0xDFFC)]
+        );
+
+        let backtrace = stack.backtrace(0x0305, 1, &SymbolTable::new());
+        assert_eq!(
+            backtrace,
+            "  #0  0305\n  #1  0163  (return from call 0300)\n"
+        );
+
+        for depth in 0..MAX_DEPTH as u16 + 10 {
+            stack.enter(frame(CallKind::Call, 0, depth, 0xDF00 - depth * 2));
+        }
+        assert_eq!(stack.frames().len(), MAX_DEPTH);
+        assert_eq!(stack.frames()[MAX_DEPTH - 1].target, MAX_DEPTH as u16 + 9);
+
+        // Overflow checks
+        assert!(is_stack_ram(0xC000, false) && is_stack_ram(0xFFFE, false));
+        assert!(!is_stack_ram(0x7FFF, true) && !is_stack_ram(0xE000, true));
+        assert!(!is_stack_ram(0xFF7F, true));
+    }
+
+    #[test]
+    fn cart_ram_is_only_stack_ram_when_mapped() {
+        assert!(is_stack_ram(0xA000, true) && is_stack_ram(0xBFFF, true));
+        assert!(!is_stack_ram(0xA000, false) && !is_stack_ram(0xBFFF, false));
+    }
+
+    #[test]
+    fn backtrace_lists_return_addresses() {
+        let mut stack = CallStack::new();
+        stack.enter(frame(CallKind::Call, 0x0150, 0x0200, 0xDFFC));
+        stack.enter(frame(CallKind::Rst, 0x0210, 0x0038, 0xDFFA));
+        stack.enter(frame(CallKind::Interrupt, 0x0039, 0x0040, 0xDFF8));
+
+        let backtrace = stack.backtrace(0x0042, 1, &SymbolTable::new());
+        assert_eq!(
+            backtrace,
+            "  #0  0042\n  #1  0039  (return from interrupt 0040)\n  #2  0211  (return from rst 0038)\n  #3  0153  (return from call 0200)\n"
+        );
+    }
+}
@@ -0,0 +1,1047 @@
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use log::{debug, info, log_enabled, warn, Level};
+
+use crate::hdw::patch::{apply_patch_file, find_patch};
+use crate::hdw::state::{find_save_files, format_timestamp, Savestate, StateReader, StateWriter};
+/*
+
+--TODO--
+
+Bank valid Cart Data From Resource
+
+Load Rom
+
+Error Handle
+
+Print Info
+
+Checksum
+
+*/
+
+struct CartridgeHeader {
+    //entry_point: [u8; 4],
+    //nintendo_logo: [u8; 0x30],
+    rom_title: [u8; 16],
+    cgb_flag: u8,
+    new_lic_code: [u8; 2],
+    sgb_flag: u8,
+    cart_type: u8,
+    rom_size: u8,
+    ram_size: u8,
+    dest_code: u8,
+    old_lic_code: u8,
+    version: u8,
+    checksum: u8,
+    global_checksum: u16,
+}
+
+/*
+
+    Memory Bank Controllers
+
+    ROM Only : 32KB mapped straight into 0x0000 - 0x7FFF, optional 8KB RAM at 0xA000
+    MBC1     : 0x0000 - 0x1FFF RAM Enable (0x0A in the low nibble)
+               0x2000 - 0x3FFF ROM Bank (5 bits, 0 -> 1)
+               0x4000 - 0x5FFF RAM Bank / Upper ROM Bank (2 bits)
+               0x6000 - 0x7FFF Banking Mode (1 -> upper bits also apply to 0x0000 and RAM)
+    MBC1M    : MBC1 multicart wiring, the upper bits land on ROM bank bit 4 instead of 5
+               so each game sits in its own 256KB (16 bank) block
+
+*/
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Mbc {
+    RomOnly,
+    Mbc1,
+}
+
+impl Mbc {
+    // Function to find the controller for a header cart type, None if it isn't supported
+    fn from_cart_type(cart_type: u8) -> Option<Mbc> {
+        match cart_type {
+            0x00 | 0x08 | 0x09 => Some(Mbc::RomOnly),
+            0x01..=0x03 => Some(Mbc::Mbc1),
+            _ => None,
+        }
+    }
+}
+
+// Result of checking a ROM image without loading it
+pub struct RomCheck {
+    pub title: String,
+    pub mapper: String,
+    pub supported: bool,
+    pub header_checksum_ok: bool,
+    pub global_checksum_ok: bool,
+    pub expected_size: usize,
+    pub actual_size: usize,
+}
+
+// Function to calculate the header checksum over 0x0134 - 0x014C
+fn header_checksum(data: &[u8]) -> u8 {
+    data[0x0134..=0x014C].iter().fold(0u8, |checksum, &byte| {
+        checksum.wrapping_sub(byte).wrapping_sub(1)
+    })
+}
+
+// Function to calculate the global checksum (every byte except the checksum itself)
+fn global_checksum(data: &[u8]) -> u16 {
+    data.iter()
+        .enumerate()
+        .filter(|&(address, _)| address != 0x014E && address != 0x014F)
+        .fold(0u16, |checksum, (_, &byte)| {
+            checksum.wrapping_add(byte as u16)
+        })
+}
+
+// Function to check a whole ROM image's header, checksums and size
+pub fn check_rom(data: &[u8]) -> Result<RomCheck, String> {
+    if data.len() < 0x150 {
+        return Err(format!(
+            "ROM file is truncated: {} bytes is smaller than the header",
+            data.len()
+        ));
+    }
+    let header = CartridgeHeader::parse(data);
+    let expected_size = header.rom_bytes().ok_or(format!(
+        "ROM size code is invalid: {:#04X}",
+        header.rom_size
+    ))?;
+
+    Ok(RomCheck {
+        title: String::from_utf8_lossy(&header.rom_title)
+            .trim_end_matches('\0')
+            .to_string(),
+        mapper: header
+            .cart_type_lookup()
+            .map(String::from)
+            .unwrap_or(format!("Unknown {:#04X}", header.cart_type)),
+        supported: Mbc::from_cart_type(header.cart_type).is_some(),
+        header_checksum_ok: header_checksum(data) == header.checksum,
+        global_checksum_ok: global_checksum(data) == header.global_checksum,
+        expected_size,
+        actual_size: data.len(),
+    })
+}
+
+pub struct Cartridge {
+    file_name: String,
+    rom_size: usize,
+    rom_data: Vec<u8>,
+    rom_header: CartridgeHeader,
+
+    // Banking
+    mbc: Mbc,
+    multicart: bool,
+    ram_data: Vec<u8>,
+    ram_enabled: bool,
+    rom_bank: u8,
+    upper_bank: u8,
+    banking_mode: bool,
+}
+
+impl Cartridge {
+    pub fn new() -> Cartridge {
+        let cartridge = Cartridge {
+            file_name: String::new(),
+            rom_size: 0,
+            rom_data: Vec::<u8>::new(),
+            rom_header: CartridgeHeader::new(),
+
+            mbc: Mbc::RomOnly,
+            multicart: false,
+            ram_data: Vec::<u8>::new(),
+            ram_enabled: false,
+            rom_bank: 1,
+            upper_bank: 0,
+            banking_mode: false,
+        };
+        cartridge
+    }
+    // Function to load in cartridge
+    pub fn load_cart(&mut self, file_path: &str) -> Result<(), String> {
+        // Update File Name
+        self.file_name = file_path.to_string();
+
+        // Open the cartridge file
+        let mut file = File::open(file_path)
+            .map_err(|e| format!("Failed to open: {}. Error: {}", file_path, e))?;
+        info!("Opened: {}", self.file_name);
+
+        // Seek to end of the file to update file size
+        file.seek(SeekFrom::End(0))
+            .map_err(|e| format!("Error Seeking File: {}", e))?;
+        self.rom_size = file
+            .metadata()
+            .map_err(|e| format!("Error Getting File Length {}", e))?
+            .len() as usize;
+
+        // Rewind to start
+        file.seek(SeekFrom::Start(0))
+            .map_err(|e| format!("Error Rewinding File {}", e))?;
+
+        // Allocate Mem Size
+        self.rom_data.resize(self.rom_size, 0);
+        file.read_exact(&mut self.rom_data)
+            .map_err(|e| format!("Failed to Read Rom Data {}", e))?;
+
+        debug!("Cartidge Loaded: {} bytes", self.rom_data.len());
+
+        // Soft patch translations/hacks sitting next to the ROM
+        if let Some(patch_path) = find_patch(Path::new(file_path)) {
+            self.rom_data = apply_patch_file(&self.rom_data, &patch_path)?;
+            info!("Patched with: {}", patch_path.display());
+        }
+
+        let rom_data = std::mem::take(&mut self.rom_data);
+        self.load_data(file_path, rom_data)
+    }
+
+    // Function to load a cartridge from ROM contents already in memory
+    // name is only used for display and crash reports
+    pub fn load_data(&mut self, name: &str, rom_data: Vec<u8>) -> Result<(), String> {
+        self.file_name = name.to_string();
+        self.rom_data = rom_data;
+
+        // Need at least a full header
+        if self.rom_data.len() < 0x150 {
+            return Err(format!(
+                "ROM file is truncated: {} bytes is smaller than the header",
+                self.rom_data.len()
+            ));
+        }
+
+        /* Print entire cartridge content in hex
+        println!("\nROM Data (Hex):");
+        for (i, byte) in self.rom_data.iter().enumerate() {
+            if *byte != 0 {
+                print!("{:02X} ", byte);
+            }
+        }
+        println!(); // Ensure the last line is properly ended
+        */
+
+        // Load Header Information
+        self.rom_header = CartridgeHeader::parse(&self.rom_data);
+
+        // Calculate the actual ROM size per pandocs
+        self.rom_size = self.rom_header.rom_bytes().ok_or(format!(
+            "ROM size code is invalid: {:#04X}",
+            self.rom_header.rom_size
+        ))?;
+        if self.rom_data.len() < self.rom_size {
+            return Err(format!(
+                "ROM file is truncated: {} bytes but the header says {} bytes",
+                self.rom_data.len(),
+                self.rom_size
+            ));
+        }
+
+        // Perform Checksum Test
+        self.checksum_test()?;
+
+        // Setup the Memory Bank Controller and Cartridge RAM
+        self.mbc = match Mbc::from_cart_type(self.rom_header.cart_type) {
+            Some(mbc) => mbc,
+            None => {
+                // Anything else runs as ROM only (bank 1 stays mapped) so warn that banking is broken
+                warn!(
+                    "{} unsupported - game may not run, falling back to ROM only",
+                    self.rom_header
+                        .cart_type_lookup()
+                        .unwrap_or("Unknown Mapper")
+                );
+                Mbc::RomOnly
+            }
+        };
+        self.multicart = self.mbc == Mbc::Mbc1 && self.is_multicart();
+        self.ram_data = vec![0; self.rom_header.ram_bytes()];
+
+        // Print Cartridge Information
+        if log_enabled!(Level::Info) {
+            self.print_info();
+        }
+
+        Ok(())
+    }
+
+    // Function to read only the header of a cartridge (for showing info without loading the ROM)
+    pub fn load_header(&mut self, file_path: &str) -> Result<(), String> {
+        self.file_name = file_path.to_string();
+
+        let mut file = File::open(file_path)
+            .map_err(|e| format!("Failed to open: {}. Error: {}", file_path, e))?;
+
+        // Header ends at 0x014F
+        let mut header = [0u8; 0x150];
+        file.read_exact(&mut header)
+            .map_err(|e| format!("Failed to Read Header {}", e))?;
+        self.rom_header = CartridgeHeader::parse(&header);
+
+        Ok(())
+    }
+
+    pub fn print_info(&self) {
+        println!("Cartridge Information:");
+        println!(
+            "  Title            : {:?}",
+            std::str::from_utf8(&self.rom_header.rom_title)
+                .unwrap_or("Invalid UTF-8")
+                .trim_end_matches('\0')
+        );
+        println!(
+            "  New License Code : {:?} ({})",
+            String::from_utf8_lossy(&self.rom_header.new_lic_code),
+            self.rom_header.new_license_lookup().unwrap_or("UNKNOWN")
+        );
+        println!(
+            "  CGB Flag         : {:#02X} ({})",
+            self.rom_header.cgb_flag,
+            match self.rom_header.cgb_flag {
+                0x80 => "CGB enhanced",
+                0xC0 => "CGB only",
+                _ => "DMG",
+            }
+        );
+        println!("  SGB Flag         : {:#02X}", self.rom_header.sgb_flag);
+        println!(
+            "  Cartridge Type   : {:#02X} ({})",
+            self.rom_header.cart_type,
+            self.rom_header.cart_type_lookup().unwrap_or("UNKNOWN")
+        );
+        match self.rom_header.rom_bytes() {
+            Some(bytes) => println!("  ROM Size         : {} KB", bytes / 1024),
+            None => println!(
+                "  ROM Size         : {:#02X} (INVALID)",
+                self.rom_header.rom_size
+            ),
+        }
+        println!(
+            "  RAM Size         : {:#02X} ({} KB)",
+            self.rom_header.ram_size,
+            self.rom_header.ram_bytes() / 1024
+        );
+        println!(
+            "  Battery          : {}",
+            if self.rom_header.has_battery() {
+                "Yes"
+            } else {
+                "No"
+            }
+        );
+        if self.multicart {
+            println!("  MBC1 Multicart   : Detected");
+        }
+        println!(
+            "  Destination Code : {:#02X} ({})",
+            self.rom_header.dest_code,
+            if self.rom_header.dest_code == 0x00 {
+                "Japan and possibly overseas"
+            } else {
+                "Overseas only"
+            }
+        );
+        println!(
+            "  Old Licensee Code: {:#02X} ({})",
+            self.rom_header.old_lic_code,
+            self.rom_header.old_license_lookup().unwrap_or("UNKNOWN")
+        );
+        println!("  Version Number   : {:#02X}", self.rom_header.version);
+        println!(
+            "  Global Checksum  : {:#02X}",
+            self.rom_header.global_checksum
+        );
+
+        // Save preview
+        let saves = find_save_files(Path::new(&self.file_name));
+        match saves.battery {
+            Some((path, time)) => println!(
+                "  Battery Save     : {} ({})",
+                path.display(),
+                format_timestamp(time)
+            ),
+            None => println!("  Battery Save     : None"),
+        }
+        if saves.states.is_empty() {
+            println!("  Save States      : None");
+        }
+        for (path, time) in &saves.states {
+            println!(
+                "  Save State       : {} ({})",
+                path.display(),
+                format_timestamp(*time)
+            );
+        }
+    }
+
+    fn checksum_test(&self) -> Result<(), String> {
+        let checksum = header_checksum(&self.rom_data);
+
+        // Check if the calculated checksum matches the stored checksum
+        if checksum == self.rom_header.checksum {
+            debug!("Checksum: {:#02X} (PASSED)", checksum);
+            Ok(())
+        } else {
+            Err(format!(
+                "\tChecksum: {:#02X} (FAILED, expected: {:#02X})",
+                checksum, self.rom_header.checksum
+            ))
+        }
+    }
+
+    // Function to detect an MBC1 multicart
+    // They're all 1MB and each game has its own header (and Nintendo logo) at the start of every 256KB block
+    fn is_multicart(&self) -> bool {
+        if self.rom_data.len() != 0x100000 {
+            return false;
+        }
+        let logo = &self.rom_data[0x0104..0x0134];
+        (1..4).all(|game| {
+            let start = game * 0x40000 + 0x0104;
+            &self.rom_data[start..start + 0x30] == logo
+        })
+    }
+
+    // Method to put the banking registers back to power on (cart RAM is kept like a battery would)
+    pub fn reset(&mut self) {
+        self.ram_enabled = false;
+        self.rom_bank = 1;
+        self.upper_bank = 0;
+        self.banking_mode = false;
+    }
+
+    // Method to carry cart RAM over from another cart (a reloaded build of the same game)
+    // Returns false if the RAM sizes differ and nothing was copied
+    pub fn copy_ram_from(&mut self, other: &Cartridge) -> bool {
+        if self.ram_data.len() != other.ram_data.len() {
+            return false;
+        }
+        self.ram_data.copy_from_slice(&other.ram_data);
+        true
+    }
+
+    // Method to get the path the ROM was loaded from
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    // Method to get the raw ROM contents
+    pub fn rom_data(&self) -> &[u8] {
+        &self.rom_data
+    }
+
+    // Method to get the header checksum byte (the boot ROM's flags depend on it)
+    pub fn header_checksum_byte(&self) -> u8 {
+        self.rom_header.checksum
+    }
+
+    // Method to get the header's global checksum (identifies the game for save states)
+    pub fn global_checksum(&self) -> u16 {
+        self.rom_header.global_checksum
+    }
+
+    // Method to get the ROM bank currently mapped at 0x4000 - 0x7FFF
+    pub fn rom_bank(&self) -> u8 {
+        match self.mbc {
+            Mbc::RomOnly => 1,
+            Mbc::Mbc1 => {
+                // Bank 0 can't be selected -> reads as 1 (checked on all 5 bits even on multicarts)
+                let low = if self.rom_bank == 0 { 1 } else { self.rom_bank };
+                let bank = if self.multicart {
+                    self.upper_bank << 4 | (low & 0x0F)
+                } else {
+                    self.upper_bank << 5 | low
+                };
+                self.wrap_bank(bank)
+            }
+        }
+    }
+
+    // Method to get the cartridge RAM bank mapped at 0xA000 - 0xBFFF
+    pub fn ram_bank(&self) -> u8 {
+        match self.mbc {
+            Mbc::Mbc1 if self.banking_mode => self.upper_bank,
+            _ => 0,
+        }
+    }
+
+    // Method to check if cartridge RAM is enabled
+    pub fn ram_enabled(&self) -> bool {
+        self.ram_enabled
+    }
+
+    // Method to check if writes to 0xA000 - 0xBFFF reach cartridge RAM (present and enabled)
+    pub fn ram_mapped(&self) -> bool {
+        self.ram_offset(0xA000).is_some()
+    }
+
+    // Function to get the ROM bank mapped at 0x0000 - 0x3FFF
+    fn zero_bank(&self) -> u8 {
+        match self.mbc {
+            Mbc::Mbc1 if self.banking_mode => {
+                let shift = if self.multicart { 4 } else { 5 };
+                self.wrap_bank(self.upper_bank << shift)
+            }
+            _ => 0,
+        }
+    }
+
+    // Function to wrap a bank number to the banks the ROM actually has
+    fn wrap_bank(&self, bank: u8) -> u8 {
+        let banks = (self.rom_data.len() / 0x4000).max(2);
+        (bank as usize % banks) as u8
+    }
+
+    // Function to find the offset into cartridge RAM for an address
+    fn ram_offset(&self, address: u16) -> Option<usize> {
+        // Only MBCs gate RAM behind an enable
+        if self.ram_data.is_empty() || (self.mbc != Mbc::RomOnly && !self.ram_enabled) {
+            return None;
+        }
+        let bank = self.ram_bank() as usize;
+        Some((bank * 0x2000 + (address as usize - 0xA000)) % self.ram_data.len())
+    }
+
+    // Method to find the offset into the ROM an address is currently mapped to
+    pub fn rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x0000..=0x3FFF => Some(self.zero_bank() as usize * 0x4000 + address as usize),
+            0x4000..=0x7FFF => {
+                Some(self.rom_bank() as usize * 0x4000 + (address as usize - 0x4000))
+            }
+            _ => None,
+        }
+    }
+
+    // Method to read a byte at an address
+    pub fn read_byte(&self, address: u16) -> u8 {
+        match self.rom_offset(address) {
+            Some(offset) => self.rom_data[offset],
+            None => match self.ram_offset(address) {
+                Some(offset) => self.ram_data[offset],
+                // Disabled or missing RAM reads as open bus
+                None => 0xFF,
+            },
+        }
+    }
+
+    // Method to write a value to an address
+    pub fn write_byte(&mut self, address: u16, value: u8) {
+        match (self.mbc, address) {
+            // MBC Registers
+            (Mbc::Mbc1, 0x0000..=0x1FFF) => self.ram_enabled = value & 0x0F == 0x0A,
+            (Mbc::Mbc1, 0x2000..=0x3FFF) => self.rom_bank = value & 0x1F,
+            (Mbc::Mbc1, 0x4000..=0x5FFF) => self.upper_bank = value & 0x03,
+            (Mbc::Mbc1, 0x6000..=0x7FFF) => self.banking_mode = value & 0x01 != 0,
+            // ROM is read only
+            (_, 0x0000..=0x7FFF) => {}
+            // Cartridge RAM
+            _ => {
+                if let Some(offset) = self.ram_offset(address) {
+                    self.ram_data[offset] = value;
+                }
+            }
+        }
+    }
+}
+
+// Only banking registers and RAM are saved, the ROM comes from the file
+impl Default for Cartridge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Savestate for Cartridge {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.section(b"CART");
+        writer.write_bool(self.ram_enabled);
+        writer.write_u8(self.rom_bank);
+        writer.write_u8(self.upper_bank);
+        writer.write_bool(self.banking_mode);
+        writer.write_bytes(&self.ram_data);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        reader.section(b"CART")?;
+        self.ram_enabled = reader.read_bool()?;
+        self.rom_bank = reader.read_u8()?;
+        self.upper_bank = reader.read_u8()?;
+        self.banking_mode = reader.read_bool()?;
+        reader.read_bytes_into(&mut self.ram_data)
+    }
+}
+
+impl CartridgeHeader {
+    // Constructor
+    pub fn new() -> CartridgeHeader {
+        let cartridge_header = CartridgeHeader {
+            //entry_point: [0; 4],
+            //nintendo_logo: [0; 0x30],
+            rom_title: [0; 16],
+            cgb_flag: 0,
+            new_lic_code: [0; 2],
+            sgb_flag: 0,
+            cart_type: 0,
+            rom_size: 0,
+            ram_size: 0,
+            dest_code: 0,
+            old_lic_code: 0,
+            version: 0,
+            checksum: 0,
+            global_checksum: 0,
+        };
+        cartridge_header
+    }
+    // Function to parse the header out of the start of a ROM (needs at least 0x150 bytes)
+    fn parse(data: &[u8]) -> CartridgeHeader {
+        CartridgeHeader {
+            //entry_point: [0; 4],
+            //nintendo_logo: [0; 0x30],
+            rom_title: data[0x0134..0x0144]
+                .try_into()
+                .expect("Failed to read ROM title"),
+            cgb_flag: data[0x0143],
+            new_lic_code: [data[0x0144], data[0x0145]],
+            sgb_flag: data[0x0146],
+            cart_type: data[0x0147],
+            rom_size: data[0x0148],
+            ram_size: data[0x0149],
+            dest_code: data[0x014A],
+            old_lic_code: data[0x014B],
+            version: data[0x014C],
+            checksum: data[0x014D],
+            global_checksum: u16::from_be_bytes([data[0x014E], data[0x014F]]),
+        }
+    }
+
+    // Function to get the ROM size in bytes, None for codes past 8 MB (0x08)
+    fn rom_bytes(&self) -> Option<usize> {
+        match self.rom_size {
+            0x00..=0x08 => Some((32 * 1024) << self.rom_size),
+            _ => None,
+        }
+    }
+
+    // Function to get the cartridge RAM size in bytes
+    fn ram_bytes(&self) -> usize {
+        match self.ram_size {
+            0x01 => 0x800,
+            0x02 => 0x2000,
+            0x03 => 0x8000,
+            0x04 => 0x20000,
+            0x05 => 0x10000,
+            _ => 0,
+        }
+    }
+
+    // Function to check if the cart type has a battery backed save
+    fn has_battery(&self) -> bool {
+        matches!(
+            self.cart_type,
+            0x03 | 0x06 | 0x09 | 0x0D | 0x0F | 0x10 | 0x13 | 0x1B | 0x1E | 0x22 | 0xFF
+        )
+    }
+
+    // Function to lookup publisher code
+    fn new_license_lookup(&self) -> Option<&'static str> {
+        match NEW_LICENSEE_CODES.get(String::from_utf8_lossy(&self.new_lic_code).as_ref()) {
+            Some(&publisher) => Some(publisher),
+            None => None,
+        }
+    }
+
+    // Function to lookup cart type
+    fn cart_type_lookup(&self) -> Option<&'static str> {
+        // Format the cart_type as a two-digit hexadecimal string
+        let key = self.cart_type;
+        // Use the key to look up in the HashMap
+        match ROM_TYPES.get(&key) {
+            Some(&cart_type) => Some(cart_type),
+            None => None,
+        }
+    }
+
+    fn old_license_lookup(&self) -> Option<&'static str> {
+        match OLD_LICENSEE_CODES.get(&format!("{:02X}", self.old_lic_code).as_str()) {
+            Some(&publisher) => Some(publisher),
+            None => None,
+        }
+    }
+}
+
+lazy_static! {
+    static ref NEW_LICENSEE_CODES: HashMap<&'static str, &'static str> = {
+        let mut map = HashMap::new();
+        map.insert("00", "None");
+        map.insert("01", "Nintendo Research & Development 1");
+        map.insert("08", "Capcom");
+        map.insert("13", "EA (Electronic Arts)");
+        map.insert("18", "Hudson Soft");
+        map.insert("19", "B-AI");
+        map.insert("20", "KSS");
+        map.insert("22", "Planning Office WADA");
+        map.insert("24", "PCM Complete");
+        map.insert("25", "San-X");
+        map.insert("28", "Kemco");
+        map.insert("29", "SETA Corporation");
+        map.insert("30", "Viacom");
+        map.insert("31", "Nintendo");
+        map.insert("32", "Bandai");
+        map.insert("33", "Ocean Software/Acclaim Entertainment");
+        map.insert("34", "Konami");
+        map.insert("35", "HectorSoft");
+        map.insert("37", "Taito");
+        map.insert("38", "Hudson Soft");
+        map.insert("39", "Banpresto");
+        map.insert("41", "Ubi Soft");
+        map.insert("42", "Atlus");
+        map.insert("44", "Malibu Interactive");
+        map.insert("46", "Angel");
+        map.insert("47", "Bullet-Proof Software");
+        map.insert("49", "Irem");
+        map.insert("50", "Absolute");
+        map.insert("51", "Acclaim Entertainment");
+        map.insert("52", "Activision");
+        map.insert("53", "Sammy USA Corporation");
+        map.insert("54", "Konami");
+        map.insert("55", "Hi Tech Expressions");
+        map.insert("56", "LJN");
+        map.insert("57", "Matchbox");
+        map.insert("58", "Mattel");
+        map.insert("59", "Milton Bradley Company");
+        map.insert("60", "Titus Interactive");
+        map.insert("61", "Virgin Games Ltd.");
+        map.insert("64", "Lucasfilm Games");
+        map.insert("67", "Ocean Software");
+        map.insert("69", "EA (Electronic Arts)");
+        map.insert("70", "Infogrames");
+        map.insert("71", "Interplay Entertainment");
+        map.insert("72", "Broderbund");
+        map.insert("73", "Sculptured Software");
+        map.insert("75", "The Sales Curve Limited");
+        map.insert("78", "THQ");
+        map.insert("79", "Accolade");
+        map.insert("80", "Misawa Entertainment");
+        map.insert("83", "lozc");
+        map.insert("86", "Tokuma Shoten");
+        map.insert("87", "Tsukuda Original");
+        map.insert("91", "Chunsoft Co.");
+        map.insert("92", "Video System");
+        map.insert("93", "Ocean Software/Acclaim Entertainment");
+        map.insert("95", "Varie");
+        map.insert("96", "Yonezawa/s’pal");
+        map.insert("97", "Kaneko");
+        map.insert("99", "Pack-In-Video");
+        map.insert("9H", "Bottom Up");
+        map.insert("A4", "Konami (Yu-Gi-Oh!)");
+        map.insert("BL", "MTO");
+        map.insert("DK", "Kodansha");
+        map
+    };
+}
+
+lazy_static! {
+    static ref ROM_TYPES: HashMap<u8, &'static str> = {
+        let mut map = HashMap::new();
+        map.insert(0x00, "ROM ONLY");
+        map.insert(0x01, "MBC1");
+        map.insert(0x02, "MBC1+RAM");
+        map.insert(0x03, "MBC1+RAM+BATTERY");
+        map.insert(0x05, "MBC2");
+        map.insert(0x06, "MBC2+BATTERY");
+        map.insert(0x08, "ROM+RAM");
+        map.insert(0x09, "ROM+RAM+BATTERY");
+        map.insert(0x0B, "MMM01");
+        map.insert(0x0C, "MMM01+RAM");
+        map.insert(0x0D, "MMM01+RAM+BATTERY");
+        map.insert(0x0F, "MBC3+TIMER+BATTERY");
+        map.insert(0x10, "MBC3+TIMER+RAM+BATTERY");
+        map.insert(0x11, "MBC3");
+        map.insert(0x12, "MBC3+RAM");
+        map.insert(0x13, "MBC3+RAM+BATTERY");
+        map.insert(0x19, "MBC5");
+        map.insert(0x1A, "MBC5+RAM");
+        map.insert(0x1B, "MBC5+RAM+BATTERY");
+        map.insert(0x1C, "MBC5+RUMBLE");
+        map.insert(0x1D, "MBC5+RUMBLE+RAM");
+        map.insert(0x1E, "MBC5+RUMBLE+RAM+BATTERY");
+        map.insert(0x20, "MBC6");
+        map.insert(0x22, "MBC7+SENSOR+RUMBLE+RAM+BATTERY");
+        map.insert(0xFC, "POCKET CAMERA");
+        map.insert(0xFD, "BANDAI TAMA5");
+        map.insert(0xFE, "HuC3");
+        map.insert(0xFF, "HuC1+RAM+BATTERY");
+        map
+    };
+}
+
+lazy_static! {
+    static ref OLD_LICENSEE_CODES: HashMap<&'static str, &'static str> = {
+        let mut map = HashMap::new();
+        map.insert("00", "None");
+        map.insert("01", "Nintendo");
+        map.insert("08", "Capcom");
+        map.insert("09", "HOT-B");
+        map.insert("0A", "Jaleco");
+        map.insert("0B", "Coconuts Japan");
+        map.insert("0C", "Elite Systems");
+        map.insert("13", "EA (Electronic Arts)");
+        map.insert("18", "Hudson Soft");
+        map.insert("19", "ITC Entertainment");
+        map.insert("1A", "Yanoman");
+        map.insert("1D", "Japan Clary");
+        map.insert("1F", "Virgin Games Ltd.");
+        map.insert("24", "PCM Complete");
+        map.insert("25", "San-X");
+        map.insert("28", "Kemco");
+        map.insert("29", "SETA Corporation");
+        map.insert("30", "Infogrames");
+        map.insert("31", "Nintendo");
+        map.insert("32", "Bandai");
+        map.insert("33", "Use New Code");
+        map.insert("34", "Konami");
+        map.insert("35", "HectorSoft");
+        map.insert("38", "Capcom");
+        map.insert("39", "Banpresto");
+        map.insert("3C", "Entertainment Interactive (stub)");
+        map.insert("3E", "Gremlin");
+        map.insert("41", "Ubi Soft");
+        map.insert("42", "Atlus");
+        map.insert("44", "Malibu Interactive");
+        map.insert("46", "Angel");
+        map.insert("47", "Spectrum HoloByte");
+        map.insert("49", "Irem");
+        map.insert("4A", "Virgin Games Ltd.");
+        map.insert("4D", "Malibu Interactive");
+        map.insert("4F", "U.S. Gold");
+        map.insert("50", "Absolute");
+        map.insert("51", "Acclaim Entertainment");
+        map.insert("52", "Activision");
+        map.insert("53", "Sammy USA Corporation");
+        map.insert("54", "GameTek");
+        map.insert("55", "Park Place");
+        map.insert("56", "LJN");
+        map.insert("57", "Matchbox");
+        map.insert("59", "Milton Bradley Company");
+        map.insert("5A", "Mindscape");
+        map.insert("5B", "Romstar");
+        map.insert("5C", "Naxat Soft");
+        map.insert("5D", "Tradewest");
+        map.insert("60", "Titus Interactive");
+        map.insert("61", "Virgin Games Ltd.");
+        map.insert("67", "Ocean Software");
+        map.insert("69", "EA (Electronic Arts)");
+        map.insert("6E", "Elite Systems");
+        map.insert("6F", "Electro Brain");
+        map.insert("70", "Infogrames");
+        map.insert("71", "Interplay Entertainment");
+        map.insert("72", "Broderbund");
+        map.insert("73", "Sculptured Software");
+        map.insert("75", "The Sales Curve Limited");
+        map.insert("78", "THQ");
+        map.insert("79", "Accolade");
+        map.insert("7A", "Triffix Entertainment");
+        map.insert("7C", "MicroProse");
+        map.insert("7F", "Kemco");
+        map.insert("80", "Misawa Entertainment");
+        map.insert("83", "LOZC G.");
+        map.insert("86", "Tokuma Shoten");
+        map.insert("8B", "Bullet-Proof Software");
+        map.insert("8C", "Vic Tokai Corp.");
+        map.insert("8E", "Ape Inc.");
+        map.insert("8F", "I’Max");
+        map.insert("91", "Chunsoft Co.");
+        map.insert("92", "Video System");
+        map.insert("93", "Tsubaraya Productions");
+        map.insert("95", "Varie");
+        map.insert("96", "Yonezawa/S’Pal");
+        map.insert("97", "Kemco");
+        map.insert("99", "Arc");
+        map.insert("9A", "Nihon Bussan");
+        map.insert("9B", "Tecmo");
+        map.insert("9C", "Imagineer");
+        map.insert("9D", "Banpresto");
+        map.insert("9F", "Nova");
+        map.insert("A1", "Hori Electric");
+        map.insert("A2", "Bandai");
+        map.insert("A4", "Konami");
+        map.insert("A6", "Kawada");
+        map.insert("A7", "Takara");
+        map.insert("A9", "Technos Japan");
+        map.insert("AA", "Broderbund");
+        map.insert("AC", "Toei Animation");
+        map.insert("AD", "Toho");
+        map.insert("AF", "Namco");
+        map.insert("B0", "Acclaim Entertainment");
+        map.insert("B1", "ASCII Corporation or Nexsoft");
+        map.insert("B2", "Bandai");
+        map.insert("B4", "Square Enix");
+        map.insert("B6", "HAL Laboratory");
+        map.insert("B7", "SNK");
+        map.insert("B9", "Pony Canyon");
+        map.insert("BA", "Culture Brain");
+        map.insert("BB", "Sunsoft");
+        map.insert("BD", "Sony Imagesoft");
+        map.insert("BF", "Sammy Corporation");
+        map.insert("C0", "Taito");
+        map.insert("C2", "Kemco");
+        map.insert("C3", "Square");
+        map.insert("C4", "Tokuma Shoten");
+        map.insert("C5", "Data East");
+        map.insert("C6", "Tonkin House");
+        map.insert("C8", "Koei");
+        map.insert("C9", "UFL");
+        map.insert("CA", "Ultra Games");
+        map.insert("CB", "VAP, Inc.");
+        map.insert("CC", "Use Corporation");
+        map.insert("CD", "Meldac");
+        map.insert("CE", "Pony Canyon");
+        map.insert("CF", "Angel");
+        map.insert("D0", "Taito");
+        map.insert("D1", "SOFEL (Software Engineering Lab)");
+        map.insert("D2", "Quest");
+        map.insert("D3", "Sigma Enterprises");
+        map.insert("D4", "ASK Kodansha Co.");
+        map.insert("D6", "Naxat Soft");
+        map.insert("D7", "Copya System");
+        map.insert("D9", "Banpresto");
+        map.insert("DA", "Tomy");
+        map.insert("DB", "LJN");
+        map.insert("DD", "Nippon Computer Systems");
+        map.insert("DE", "Human Ent.");
+        map.insert("DF", "Altron");
+        map.insert("E0", "Jaleco");
+        map.insert("E1", "Towa Chiki");
+        map.insert("E2", "Yutaka");
+        map.insert("E3", "Varie");
+        map.insert("E5", "Epoch");
+        map.insert("E7", "Athena");
+        map.insert("E8", "Asmik Ace Entertainment");
+        map.insert("E9", "Natsume");
+        map.insert("EA", "King Records");
+        map.insert("EB", "Atlus");
+        map.insert("EC", "Epic/Sony Records");
+        map.insert("EE", "IGS");
+        map.insert("F0", "A Wave");
+        map.insert("F3", "Extreme Entertainment");
+        map.insert("FF", "LJN");
+        map
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to build a ROM with a valid header checksum
+    fn rom_with_header(cart_type: u8, rom_size: u8, banks: usize) -> Vec<u8> {
+        let mut rom = vec![0; banks * 0x4000];
+        rom[0x0147] = cart_type;
+        rom[0x0148] = rom_size;
+        rom[0x014D] = header_checksum(&rom);
+        rom
+    }
+
+    // Function to load an MBC1 cart whose banks each hold their number at offset 0x10
+    fn mbc1_cart(cart_type: u8, rom_size: u8, ram_size: u8, multicart: bool) -> Cartridge {
+        let banks = 2 << rom_size;
+        let mut rom = rom_with_header(cart_type, rom_size, banks);
+        for bank in 0..banks {
+            rom[bank * 0x4000 + 0x10] = bank as u8;
+        }
+        // Multicarts repeat the logo at the start of every 256KB game
+        if multicart {
+            for game in 0..4 {
+                rom[game * 0x40000 + 0x0104..game * 0x40000 + 0x0134].fill(0xCE);
+            }
+        }
+        rom[0x0149] = ram_size;
+        rom[0x014D] = header_checksum(&rom);
+
+        let mut cart = Cartridge::new();
+        cart.load_data("mbc1.gb", rom).unwrap();
+        cart
+    }
+
+    #[test]
+    fn rejects_bad_rom_size_code() {
+        let rom = rom_with_header(0x00, 0xFF, 2);
+        assert!(check_rom(&rom).is_err());
+        assert!(Cartridge::new().load_data("bad.gb", rom).is_err());
+    }
+
+    #[test]
+    fn mbc1_bank_zero_selects_bank_one() {
+        let mut cart = mbc1_cart(0x01, 0x04, 0x00, false);
+        assert_eq!(cart.read_byte(0x4010), 1);
+
+        cart.write_byte(0x2000, 0x00);
+        assert_eq!(cart.read_byte(0x4010), 1);
+        cart.write_byte(0x2000, 0x05);
+        assert_eq!(cart.read_byte(0x4010), 5);
+        // Only 5 bits are wired
+        cart.write_byte(0x2000, 0x25);
+        assert_eq!(cart.read_byte(0x4010), 5);
+    }
+
+    #[test]
+    fn mbc1_upper_bits_extend_the_bank() {
+        let mut cart = mbc1_cart(0x01, 0x06, 0x00, false);
+        cart.write_byte(0x4000, 0x02);
+        cart.write_byte(0x2000, 0x03);
+        assert_eq!(cart.read_byte(0x4010), 0x43);
+
+        // Mode 0 keeps bank 0 at 0000, mode 1 applies the upper bits there too
+        assert_eq!(cart.read_byte(0x0010), 0x00);
+        cart.write_byte(0x6000, 0x01);
+        assert_eq!(cart.read_byte(0x0010), 0x40);
+        assert_eq!(cart.read_byte(0x4010), 0x43);
+    }
+
+    #[test]
+    fn mbc1m_uses_four_bank_bits() {
+        let mut cart = mbc1_cart(0x01, 0x05, 0x00, true);
+        assert!(cart.multicart);
+
+        cart.write_byte(0x4000, 0x01);
+        cart.write_byte(0x2000, 0x12);
+        assert_eq!(cart.read_byte(0x4010), 0x12);
+
+        // The 0 -> 1 remap looks at all 5 bits, so only 0x00 is bumped
+        cart.write_byte(0x2000, 0x10);
+        assert_eq!(cart.read_byte(0x4010), 0x10);
+        cart.write_byte(0x2000, 0x00);
+        assert_eq!(cart.read_byte(0x4010), 0x11);
+
+        cart.write_byte(0x6000, 0x01);
+        assert_eq!(cart.read_byte(0x0010), 0x10);
+    }
+
+    #[test]
+    fn mbc1_ram_needs_enabling() {
+        let mut cart = mbc1_cart(0x03, 0x04, 0x03, false);
+        cart.write_byte(0xA000, 0x42);
+        assert_eq!(cart.read_byte(0xA000), 0xFF);
+
+        cart.write_byte(0x0000, 0x0A);
+        cart.write_byte(0xA000, 0x42);
+        assert_eq!(cart.read_byte(0xA000), 0x42);
+
+        // Mode 1 banks RAM with the upper bits
+        cart.write_byte(0x4000, 0x02);
+        cart.write_byte(0x6000, 0x01);
+        assert_eq!(cart.read_byte(0xA000), 0x00);
+        cart.write_byte(0x6000, 0x00);
+        assert_eq!(cart.read_byte(0xA000), 0x42);
+
+        cart.write_byte(0x0000, 0x00);
+        assert_eq!(cart.read_byte(0xA000), 0xFF);
+    }
+}
@@ -0,0 +1,139 @@
+/*
+
+    Code/Data Logger (--cdl FILE)
+
+    One flag byte per ROM byte recording how the game used it, the same layout FCEUX/Mesen
+    style CDL files use so disassemblers can tell code from data
+        0x01 : executed as code (opcode or operand)
+        0x02 : read as data
+    An existing file for the same ROM is merged into, so coverage builds up over sessions
+
+*/
+use std::cell::Cell;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+pub const CDL_CODE: u8 = 0x01;
+pub const CDL_DATA: u8 = 0x02;
+
+pub struct CodeDataLog {
+    flags: Vec<Cell<u8>>,
+    instruction: Cell<Range<usize>>,
+}
+
+impl CodeDataLog {
+    // Constructor
+    pub fn new(rom_size: usize) -> Self {
+        CodeDataLog {
+            flags: (0..rom_size).map(|_| Cell::new(0)).collect(),
+            instruction: Cell::new(0..0),
+        }
+    }
+
+    // Function to start from an existing CDL file if there is one
+    pub fn load(path: &Path, rom_size: usize) -> Result<Self, String> {
+        let cdl = CodeDataLog::new(rom_size);
+        if !path.exists() {
+            return Ok(cdl);
+        }
+
+        let data = fs::read(path)
+            .map_err(|e| format!("Failed to open: {}. Error: {}", path.display(), e))?;
+        if data.len() != rom_size {
+            return Err(format!(
+                "CDL Size Mismatch: {} is {} bytes, the ROM is {}",
+                path.display(),
+                data.len(),
+                rom_size
+            ));
+        }
+        for (flag, byte) in cdl.flags.iter().zip(data) {
+            flag.set(byte);
+        }
+        Ok(cdl)
+    }
+
+    // Method to get the ROM size this log covers
+    pub fn len(&self) -> usize {
+        self.flags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flags.is_empty()
+    }
+
+    // Method to mark the bytes of the instruction about to run as code
+    // Its operand reads are then left out of the data marking
+    pub fn mark_instruction(&self, offset: usize, length: usize) {
+        let end = (offset + length).min(self.flags.len());
+        for flag in self.flags.get(offset..end).unwrap_or(&[]) {
+            flag.set(flag.get() | CDL_CODE);
+        }
+        self.instruction.set(offset..end);
+    }
+
+    // Method to mark a ROM byte as read for data
+    pub fn mark_data(&self, offset: usize) {
+        let instruction = self.instruction.take();
+        if !instruction.contains(&offset) {
+            if let Some(flag) = self.flags.get(offset) {
+                flag.set(flag.get() | CDL_DATA);
+            }
+        }
+        self.instruction.set(instruction);
+    }
+
+    // Method to get the flags for a ROM byte
+    pub fn flags(&self, offset: usize) -> u8 {
+        self.flags.get(offset).map_or(0, Cell::get)
+    }
+
+    // Method to count the bytes marked as code and as data
+    pub fn coverage(&self) -> (usize, usize) {
+        let count = |mask| {
+            self.flags
+                .iter()
+                .filter(|flag| flag.get() & mask != 0)
+                .count()
+        };
+        (count(CDL_CODE), count(CDL_DATA))
+    }
+
+    // Method to get the file contents
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.flags.iter().map(Cell::get).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separates_code_from_data_and_merges() {
+        let cdl = CodeDataLog::new(0x8000);
+        // LD A,(nn) at 0150 reading 4000
+        cdl.mark_instruction(0x150, 3);
+        cdl.mark_data(0x151);
+        cdl.mark_data(0x152);
+        cdl.mark_data(0x4000);
+        assert_eq!(
+            (cdl.flags(0x150), cdl.flags(0x152), cdl.flags(0x4000)),
+            (CDL_CODE, CDL_CODE, CDL_DATA)
+        );
+        assert_eq!(cdl.coverage(), (3, 1));
+
+        // Code later read as data (a jump table in the middle of code) keeps both
+        cdl.mark_instruction(0x200, 1);
+        cdl.mark_data(0x150);
+        assert_eq!(cdl.flags(0x150), CDL_CODE | CDL_DATA);
+
+        let path = std::env::temp_dir().join(format!("gb_cdl_test_{}.cdl", std::process::id()));
+        fs::write(&path, cdl.to_bytes()).unwrap();
+        let merged = CodeDataLog::load(&path, 0x8000).unwrap();
+        assert_eq!(merged.coverage(), (4, 2));
+        assert!(CodeDataLog::load(&path, 0x10000).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
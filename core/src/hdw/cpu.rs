@@ -0,0 +1,784 @@
+use crate::hdw::bus::Bus;
+use crate::hdw::call_stack::{CallFrame, CallKind, CallStack};
+use crate::hdw::cpu_ops::*;
+use crate::hdw::cycles::{branch_condition, branch_taken_cycles, opcode_cycles};
+use crate::hdw::disasm::instruction_length;
+use crate::hdw::emu::emu_cycles;
+use crate::hdw::heatmap::HeatAccess;
+use crate::hdw::instructions::*;
+use crate::hdw::interrupts::*;
+use crate::hdw::registers::*;
+use crate::hdw::state::{Savestate, StateReader, StateWriter};
+use crate::hdw::symbols::SymbolTable;
+use lazy_static::lazy_static;
+use log::warn;
+use regex::Regex;
+
+lazy_static! {
+    // Regex to capture the instruction name within `Some(...)`
+    static ref INSTRUCTION_NAME: Regex = Regex::new(r"Some\(\s*([A-Z]+)").unwrap();
+}
+
+// Number of executed instructions kept for crash reports
+pub const HISTORY_LENGTH: usize = 64;
+
+// Our CPU to Call and Control
+pub struct CPU {
+    pub registers: Registers,
+    pub pc: u16,
+    pub sp: u16,
+    pub bus: Bus,
+
+    pub curr_opcode: u8,
+    pub curr_instruction: Option<Instruction>,
+
+    pub is_halted: bool,
+    pub is_stopped: bool,
+    pub is_locked: bool,
+    pub halt_bug: bool,
+    pub is_stepping: bool,
+    pub trace: bool,
+    pub cycles: u64,
+
+    pub enabling_ime: bool,
+    pub master_enabled: bool,
+
+    pub symbols: SymbolTable,
+    pub call_stack: CallStack,
+
+    // Ring buffer of (pc, opcode) for the last executed instructions
+    history: [(u16, u8); HISTORY_LENGTH],
+    history_pos: usize,
+    history_len: usize,
+}
+impl CPU {
+    // Contructor
+    pub fn new(new_bus: Bus) -> Self {
+        CPU {
+            registers: Registers {
+                a: 0x01,
+                b: 0,
+                c: 0,
+                d: 0,
+                e: 0,
+                f: FlagsRegister {
+                    zero: false,
+                    subtract: false,
+                    half_carry: false,
+                    carry: false,
+                },
+                h: 0,
+                l: 0,
+            },
+            pc: 0x0100,
+            sp: 0,
+            bus: new_bus,
+
+            curr_opcode: 0,
+            curr_instruction: None,
+
+            is_halted: false,
+            is_stopped: false,
+            is_locked: false,
+            halt_bug: false,
+            is_stepping: true,
+            trace: false,
+            cycles: 0,
+
+            enabling_ime: false,
+            master_enabled: false,
+
+            symbols: SymbolTable::new(),
+            call_stack: CallStack::new(),
+
+            history: [(0, 0); HISTORY_LENGTH],
+            history_pos: 0,
+            history_len: 0,
+        }
+    }
+
+    // Function to 'step' through instructions
+    // Returns an error if the CPU can't continue (bad opcode)
+    pub fn step(&mut self, ticks: u64) -> Result<(), String> {
+        // Locked up by an illegal opcode -> nothing but a reset gets it back, not even interrupts
+        if self.is_locked {
+            emu_cycles(self, 1);
+            return Ok(());
+        }
+
+        // STOP -> the clock is off until a button pulls a selected joypad line low
+        if self.is_stopped {
+            if !self.bus.joypad_line_low() {
+                // Time passes but nothing on the bus is clocked (DIV/TIMA hold)
+                self.cycles += 4;
+                return Ok(());
+            }
+            self.is_stopped = false;
+        }
+
+        if !self.is_halted {
+            // fetch next opcode from cartridge
+            self.fetch();
+            self.history[self.history_pos] = (self.pc, self.curr_opcode);
+            self.history_pos = (self.history_pos + 1) % HISTORY_LENGTH;
+            self.history_len = (self.history_len + 1).min(HISTORY_LENGTH);
+
+            // HALT bug -> the byte after HALT is read twice, so run this instruction as if
+            // it started one byte earlier (its operands and return address all shift back)
+            if self.halt_bug {
+                self.pc = self.pc.wrapping_sub(1);
+                self.halt_bug = false;
+            }
+
+            // Removed opcodes hang the CPU like on hardware instead of stopping emulation
+            if Instruction::is_illegal(self.curr_opcode) {
+                warn!(
+                    "CPU locked up by illegal opcode {:02X} at {:04X}",
+                    self.curr_opcode, self.pc
+                );
+                self.is_locked = true;
+                emu_cycles(self, 1);
+                return Ok(());
+            }
+
+            // Decode current opcode
+            self.decode()?;
+
+            // Charge the base cycles for this instruction plus any taken branch
+            let mut cycles = opcode_cycles(
+                self.curr_opcode,
+                self.bus.peek_byte(self.pc.wrapping_add(1)),
+            );
+            let branch_cycles = branch_taken_cycles(self.curr_opcode);
+            if branch_cycles > 0
+                && branch_condition(
+                    self.curr_opcode,
+                    self.registers.f.zero,
+                    self.registers.f.carry,
+                )
+            {
+                cycles += branch_cycles;
+            }
+
+            // print information
+            if self.trace {
+                self.print_trace(ticks);
+            }
+
+            // Execute the current instruction if it exists and reset it to none
+            if let Some(instruction) = self.curr_instruction.take() {
+                // Execute the current instruction
+                let next_pc = self.execute(instruction);
+
+                // Increment pc to returned pc
+                self.pc = next_pc;
+                emu_cycles(self, cycles);
+            } else {
+                return Err(String::from("Decode Error: No Instruction"));
+            }
+        } else {
+            // is halted
+            emu_cycles(self, 1);
+
+            // Any pending interrupt wakes the CPU, even with IME off
+            if self.bus.int_flags & self.bus.ie_register & 0x1F != 0 {
+                self.is_halted = false;
+            }
+        }
+
+        if self.master_enabled {
+            cpu_handle_interrupts(self);
+            self.enabling_ime = false;
+        }
+
+        // EI takes effect after the instruction following it
+        if self.enabling_ime {
+            self.master_enabled = true;
+        }
+
+        Ok(())
+    }
+
+    // Method to record a CALL/RST/interrupt on the shadow call stack (return address already pushed)
+    pub fn enter_frame(&mut self, kind: CallKind, site: u16, target: u16) {
+        let frame = CallFrame {
+            kind,
+            site,
+            target,
+            sp: self.sp,
+            bank: self.bus.cart.rom_bank(),
+        };
+        self.call_stack.enter(frame);
+    }
+
+    // Method to format the shadow call stack as a backtrace from the current PC
+    pub fn backtrace(&self) -> String {
+        self.call_stack
+            .backtrace(self.pc, self.bus.cart.rom_bank(), &self.symbols)
+    }
+
+    // Function to get the last executed (pc, opcode) pairs, oldest first
+    pub fn recent_instructions(&self) -> impl Iterator<Item = (u16, u8)> + '_ {
+        let start = (self.history_pos + HISTORY_LENGTH - self.history_len) % HISTORY_LENGTH;
+        (0..self.history_len).map(move |i| self.history[(start + i) % HISTORY_LENGTH])
+    }
+
+    // Function to print the trace line for the current instruction
+    fn print_trace(&self, ticks: u64) {
+        // Convert `curr_instruction` to a string
+        let instruction_output = format!("{:#?}", self.curr_instruction);
+
+        // Use regex to capture the instruction name
+        let instruction_name = if let Some(cap) = INSTRUCTION_NAME.captures(&instruction_output) {
+            cap.get(1).map_or("Unknown", |m| m.as_str())
+        } else {
+            "Unknown"
+        };
+
+        // Print the label if we have symbols for this address
+        if let Some(label) = self.symbols.label(self.pc, self.bus.cart.rom_bank()) {
+            print!("\n{}:", label);
+        }
+
+        // Print information, including the extracted instruction name
+        print!(
+            "\n{:08X} - {:04X}: ({:02X}: {})\t[{:02X} {:02X} {:02X} {:02X}] A: {:02X} F: {}{}{}{} BC: {:04X} DE: {:04X} HL: {:04X}",
+            ticks,
+            self.pc,
+            self.curr_opcode,
+            instruction_name,
+            self.curr_opcode,
+            self.bus.peek_byte(self.pc.wrapping_add(1)),
+            self.bus.peek_byte(self.pc.wrapping_add(2)),
+            self.bus.peek_byte(self.pc.wrapping_add(3)),
+            self.registers.a,
+            if self.registers.f.zero { 'Z' } else { '-' },
+            if self.registers.f.subtract { 'N' } else { '-' },
+            if self.registers.f.half_carry { 'H' } else { '-' },
+            if self.registers.f.carry { 'C' } else { '-' },
+            self.registers.get_bc(),
+            self.registers.get_de(),
+            self.registers.get_hl(),
+        );
+    }
+
+    // Function to fetch next opcode
+    fn fetch(&mut self) {
+        if let Some(heatmap) = &self.bus.heatmap {
+            heatmap.record(self.pc, HeatAccess::Execute);
+        }
+        if let Some(cdl) = &self.bus.cdl {
+            if let Some(offset) = self.bus.cart.rom_offset(self.pc) {
+                let opcode = self.bus.peek_byte(self.pc);
+                cdl.mark_instruction(offset, instruction_length(opcode));
+            }
+        }
+        self.curr_opcode = self.bus.read_byte(self.pc);
+    }
+
+    // Function to decode current opcode
+    fn decode(&mut self) -> Result<(), String> {
+        // Try to decode curr opcode
+        self.curr_instruction =
+            Instruction::decode_from_opcode(self.curr_opcode, &self.bus, self.pc);
+
+        // Error handling
+        if self.curr_instruction.is_none() {
+            return Err(format!(
+                "Unable to Read Opcode 0x{:02X} at {:04X}",
+                self.curr_opcode, self.pc
+            ));
+        }
+        Ok(())
+    }
+
+    // Function to execute an opcode by matching Instruction type and target then calling its method
+    fn execute(&mut self, instruction: Instruction) -> u16 {
+        // return while halted
+        if self.is_halted {
+            return self.pc;
+        }
+
+        match instruction {
+            Instruction::NOP => {
+                // Do nothing -> increment pc
+                self.pc.wrapping_add(1)
+            }
+            Instruction::STOP => {
+                // DMG only -> stop the clock and reset DIV (no CGB speed switch yet)
+                self.is_stopped = true;
+                self.bus.timer.write(0xFF04, 0);
+
+                // STOP is 2 bytes, the second is skipped
+                self.pc.wrapping_add(2)
+            }
+            Instruction::RLCA => {
+                // Perform Operation & Implicit Return
+                op_rlca(self)
+            }
+            Instruction::RRCA => {
+                // Perform Operation & Implicit Return
+                op_rrca(self)
+            }
+            Instruction::RLA => {
+                // Perform Operation & Implicit Return
+                op_rla(self)
+            }
+            Instruction::RRA => {
+                // Perform Operation & Implicit Return
+                op_rra(self)
+            }
+            Instruction::DAA => {
+                // Perform Operation & Implicit Return
+                op_daa(self)
+            }
+            Instruction::SCF => {
+                self.registers.f.subtract = false;
+                self.registers.f.half_carry = false;
+                self.registers.f.carry = true;
+                self.pc.wrapping_add(1)
+            }
+            Instruction::CPL => {
+                // Perform Operation & Implicit Return
+                op_cpl(self)
+            }
+            Instruction::CCF => {
+                self.registers.f.subtract = false;
+                self.registers.f.half_carry = false;
+                self.registers.f.carry = !self.registers.f.carry;
+                self.pc.wrapping_add(1)
+            }
+            Instruction::JR(target) => {
+                // Perform Operation & Implicit Return
+                let next_pc = op_jr(self, target);
+                next_pc
+            }
+            Instruction::INC(target) => {
+                // Perform Operation & Implicit Return
+                op_inc(self, target)
+            }
+            Instruction::DEC(target) => {
+                // Perform Operation & Implicit Return
+                op_dec(self, target)
+            }
+            Instruction::LD(target) => {
+                // Perform Operation & Implicit Return
+                op_ld(self, target)
+            }
+            Instruction::HALT => {
+                // With IME off and an interrupt already pending HALT doesn't halt
+                // and the CPU fails to increment PC past the next byte
+                if !self.master_enabled && self.bus.int_flags & self.bus.ie_register & 0x1F != 0 {
+                    self.halt_bug = true;
+                } else {
+                    // Instruction For Halting CPU Cycle
+                    self.is_halted = true;
+                }
+                self.pc.wrapping_add(1)
+            }
+            Instruction::ADD(target) => {
+                // Perform Operation & Implicit Return
+                op_add(self, target)
+            }
+            Instruction::ADC(target) => {
+                // Perform Operation & Implicit Return
+                op_adc(self, target)
+            }
+            Instruction::SUB(target) => {
+                // Perform Operation & Implicit Return
+                op_sub(self, target)
+            }
+            Instruction::SBC(target) => {
+                // Perform Operation & Implicit Return
+                op_sbc(self, target)
+            }
+            Instruction::AND(target) => {
+                // Perform Operation & Implicit Return
+                op_and(self, target)
+            }
+            Instruction::XOR(target) => {
+                // Perform Operation & Implicit Return
+                op_xor(self, target)
+            }
+            Instruction::OR(target) => {
+                // Perform Operation & Implicit Return
+                op_or(self, target)
+            }
+            Instruction::CP(target) => {
+                // Perform Operation & Implicit Return
+                op_cp(self, target)
+            }
+            Instruction::RET(target) => {
+                // Perform Operation & Implicit Return
+                let sp = self.sp;
+                let next_pc = op_ret(self, target);
+                // Only a taken return moves SP
+                if self.sp != sp {
+                    self.call_stack.exit(sp);
+                }
+                //thread::sleep(Duration::from_secs(20));
+                next_pc
+            }
+            Instruction::RETI => {
+                // Perform Operation & Implicit Return
+                let sp = self.sp;
+                let next_pc = op_reti(self);
+                // Only a taken return moves SP
+                if self.sp != sp {
+                    self.call_stack.exit(sp);
+                }
+                //thread::sleep(Duration::from_secs(20));
+                next_pc
+            }
+            Instruction::POP(target) => {
+                // Perform Operation & Implicit Return
+                op_pop(self, target)
+            }
+            Instruction::JP(target) => {
+                // Perform Operation & Implicit Return
+                op_jp(self, target)
+            }
+            Instruction::CALL(target) => {
+                // Perform Operation & Implicit Return
+                let (sp, site) = (self.sp, self.pc);
+                let next_pc = op_call(self, target);
+                if self.sp != sp {
+                    self.enter_frame(CallKind::Call, site, next_pc);
+                }
+                //thread::sleep(Duration::from_secs(20));
+                next_pc
+            }
+            Instruction::PUSH(target) => {
+                // Perform Operation & Implicit Return
+                op_push(self, target)
+            }
+            Instruction::RST(target) => {
+                // Perform Operation & Implicit Return
+                let (sp, site) = (self.sp, self.pc);
+                let next_pc = op_rst(self, target);
+                if self.sp != sp {
+                    self.enter_frame(CallKind::Rst, site, next_pc);
+                }
+                //thread::sleep(Duration::from_secs(20));
+                next_pc
+            }
+            Instruction::DI => {
+                // Also cancels an EI that hasn't taken effect yet
+                self.master_enabled = false;
+                self.enabling_ime = false;
+                self.pc.wrapping_add(1) // unsure what to return here leaving this for now
+            }
+            Instruction::EI => {
+                // IME is set after the next instruction
+                self.enabling_ime = true;
+                self.pc.wrapping_add(1) // unsure what to return here leavint his for now
+            }
+
+            // PREFIXED INSTRUCTIONS
+            Instruction::RLC(target) => {
+                // Perform Operation & Implicit Return
+                op_rlc(self, target)
+            }
+            Instruction::RRC(target) => {
+                // Perform Operation & Implicit Return
+                op_rrc(self, target)
+            }
+            Instruction::RL(target) => {
+                // Perform Operation & Implicit Return
+                op_rl(self, target)
+            }
+            Instruction::RR(target) => {
+                // Perform Operation & Implicit Return
+                op_rr(self, target)
+            }
+            Instruction::SLA(target) => {
+                // Perform Operation & Implicit Return
+                op_sla(self, target)
+            }
+            Instruction::SRA(target) => {
+                // Perform Operation & Implicit Return
+                op_sra(self, target)
+            }
+            Instruction::SWAP(target) => {
+                // Perform Operation & Implicit Return
+                op_swap(self, target)
+            }
+            Instruction::SRL(target) => {
+                // Perform Operation & Implicit Return
+                op_srl(self, target)
+            }
+            Instruction::BIT(target) => {
+                // Perform Operation & Implicit Return
+                op_bit(self, target)
+            }
+            Instruction::RES(target) => {
+                // Perform Operation & Implicit Return
+                op_res(self, target)
+            }
+            Instruction::SET(target) => {
+                // Perform Operation & Implicit Return
+                op_set(self, target)
+            }
+        }
+    }
+    // CPU ENDS HERE
+}
+
+// Tracing/debug settings and symbols aren't part of the machine so they aren't saved
+impl Savestate for CPU {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.section(b"CPU ");
+        self.registers.save_state(writer);
+        writer.write_u16(self.pc);
+        writer.write_u16(self.sp);
+        writer.write_bool(self.is_halted);
+        writer.write_bool(self.is_stopped);
+        writer.write_bool(self.is_locked);
+        writer.write_bool(self.halt_bug);
+        writer.write_bool(self.enabling_ime);
+        writer.write_bool(self.master_enabled);
+        writer.write_u64(self.cycles);
+        self.bus.save_state(writer);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        reader.section(b"CPU ")?;
+        // The shadow stack isn't saved, it rebuilds as the game calls and returns
+        self.call_stack.clear();
+        self.registers.load_state(reader)?;
+        self.pc = reader.read_u16()?;
+        self.sp = reader.read_u16()?;
+        self.is_halted = reader.read_bool()?;
+        self.is_stopped = reader.read_bool()?;
+        self.is_locked = reader.read_bool()?;
+        self.halt_bug = reader.read_bool()?;
+        self.enabling_ime = reader.read_bool()?;
+        self.master_enabled = reader.read_bool()?;
+        self.cycles = reader.read_u64()?;
+        self.bus.load_state(reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdw::cart::Cartridge;
+    use crate::hdw::joypad::Button;
+    use crate::hdw::stack::stack_pop;
+    use crate::hdw::test_util::cpu_with_program;
+
+    #[test]
+    fn ei_enables_interrupts_after_next_instruction() {
+        // EI, NOP, NOP with VBLANK pending
+        let mut cpu = cpu_with_program(&[0xFB, 0x00, 0x00]);
+        cpu.bus.ie_register = 0x01;
+        cpu.bus.int_flags = 0x01;
+
+        // EI itself doesn't service the interrupt
+        cpu.step(0).unwrap();
+        assert_eq!(cpu.pc, 0xC001);
+
+        // The NOP after it runs, then the interrupt is taken
+        cpu.step(1).unwrap();
+        assert_eq!(cpu.pc, 0x0040);
+        assert_eq!(cpu.bus.int_flags, 0x00);
+        let low = stack_pop(&mut cpu) as u16;
+        let high = stack_pop(&mut cpu) as u16;
+        assert_eq!(high << 8 | low, 0xC002);
+    }
+
+    #[test]
+    fn interrupt_dispatch_takes_five_cycles() {
+        // NOP with VBLANK pending and IME on
+        let mut cpu = cpu_with_program(&[0x00]);
+        cpu.master_enabled = true;
+        cpu.bus.ie_register = 0x01;
+        cpu.bus.int_flags = 0x01;
+
+        // 1 M-cycle for the NOP, 5 for the dispatch
+        cpu.step(0).unwrap();
+        assert_eq!(cpu.pc, 0x0040);
+        assert_eq!(cpu.cycles, 6 * 4);
+    }
+
+    #[test]
+    fn di_cancels_pending_ei() {
+        // EI, DI, NOP with VBLANK pending
+        let mut cpu = cpu_with_program(&[0xFB, 0xF3, 0x00]);
+        cpu.bus.ie_register = 0x01;
+        cpu.bus.int_flags = 0x01;
+
+        for ticks in 0..3 {
+            cpu.step(ticks).unwrap();
+        }
+        assert_eq!(cpu.pc, 0xC003);
+        assert!(!cpu.master_enabled);
+        assert_eq!(cpu.bus.int_flags, 0x01);
+    }
+
+    #[test]
+    fn halt_bug_repeats_next_byte() {
+        // HALT, INC A, NOP with IME off and VBLANK pending
+        let mut cpu = cpu_with_program(&[0x76, 0x3C, 0x00]);
+        cpu.registers.a = 0;
+        cpu.bus.ie_register = 0x01;
+        cpu.bus.int_flags = 0x01;
+
+        // HALT doesn't halt
+        cpu.step(0).unwrap();
+        assert!(!cpu.is_halted);
+        assert_eq!(cpu.pc, 0xC001);
+
+        // INC A runs twice
+        cpu.step(1).unwrap();
+        cpu.step(2).unwrap();
+        assert_eq!(cpu.registers.a, 2);
+        assert_eq!(cpu.pc, 0xC002);
+    }
+
+    #[test]
+    fn halt_bug_reads_opcode_as_operand() {
+        // HALT, LD A n8 (0x3E), 0x14 -> runs LD A 0x3E then INC D (0x14)
+        let mut cpu = cpu_with_program(&[0x76, 0x3E, 0x14, 0x00]);
+        cpu.registers.d = 0;
+        cpu.bus.ie_register = 0x01;
+        cpu.bus.int_flags = 0x01;
+
+        for ticks in 0..3 {
+            cpu.step(ticks).unwrap();
+        }
+        assert_eq!(cpu.registers.a, 0x3E);
+        assert_eq!(cpu.registers.d, 1);
+        assert_eq!(cpu.pc, 0xC003);
+    }
+
+    #[test]
+    fn stop_waits_for_a_button() {
+        // Select directions, STOP, (skipped byte), NOP
+        let mut cpu = cpu_with_program(&[0x10, 0x00, 0x00]);
+        cpu.bus.write_byte(0xFF00, 0x20);
+
+        cpu.step(0).unwrap();
+        assert!(cpu.is_stopped);
+        assert_eq!(cpu.pc, 0xC002);
+
+        // The clock is stopped -> DIV doesn't count
+        let div = cpu.bus.peek_byte(0xFF04);
+        for ticks in 1..200 {
+            cpu.step(ticks).unwrap();
+        }
+        assert_eq!(cpu.bus.peek_byte(0xFF04), div);
+
+        // A button on the other row doesn't wake it
+        cpu.bus.set_button(Button::Start, true);
+        cpu.step(1).unwrap();
+        assert_eq!(cpu.pc, 0xC002);
+
+        cpu.bus.set_button(Button::Down, true);
+        cpu.step(2).unwrap();
+        assert!(!cpu.is_stopped);
+        assert_eq!(cpu.pc, 0xC003);
+    }
+
+    #[test]
+    fn illegal_opcode_locks_up() {
+        // NOP, illegal D3
+        let mut cpu = cpu_with_program(&[0x00, 0xD3, 0x00]);
+        cpu.step(0).unwrap();
+        cpu.step(1).unwrap();
+        assert!(cpu.is_locked);
+
+        // Stays put and ignores interrupts
+        cpu.master_enabled = true;
+        cpu.bus.ie_register = 0x01;
+        cpu.bus.int_flags = 0x01;
+        for tick in 0..10 {
+            cpu.step(tick).unwrap();
+        }
+        assert_eq!(cpu.pc, 0xC001);
+        assert_eq!(cpu.bus.int_flags, 0x01);
+
+        // Every other opcode decodes
+        let bus = Bus::new(Cartridge::new());
+        for opcode in
+            (0..=0xFF).filter(|&opcode| !Instruction::is_illegal(opcode) && opcode != 0xCB)
+        {
+            assert!(
+                Instruction::decode_from_opcode(opcode, &bus, 0xC000).is_some(),
+                "{:02X}",
+                opcode
+            );
+        }
+    }
+
+    #[test]
+    fn halt_wakes_on_pending_interrupt_with_ime_off() {
+        // HALT, NOP with VBLANK enabled but not requested
+        let mut cpu = cpu_with_program(&[0x76, 0x00]);
+        cpu.bus.ie_register = 0x01;
+
+        cpu.step(0).unwrap();
+        assert!(cpu.is_halted);
+
+        // A flag that isn't enabled doesn't wake it
+        cpu.bus.int_flags = 0x04;
+        cpu.step(1).unwrap();
+        assert!(cpu.is_halted);
+
+        // VBLANK wakes it without being serviced
+        cpu.bus.int_flags = 0x05;
+        cpu.step(2).unwrap();
+        assert!(!cpu.is_halted);
+        cpu.step(3).unwrap();
+        assert_eq!(cpu.pc, 0xC002);
+        assert_eq!(cpu.bus.int_flags, 0x05);
+    }
+
+    #[test]
+    fn taken_branches_charge_extra_cycles() {
+        // JR NZ +0 twice, first with Z set (not taken) then clear (taken)
+        let mut cpu = cpu_with_program(&[0x20, 0x00, 0x20, 0x00]);
+
+        cpu.registers.f.zero = true;
+        cpu.step(0).unwrap();
+        assert_eq!(cpu.cycles, 8);
+
+        cpu.registers.f.zero = false;
+        cpu.step(1).unwrap();
+        assert_eq!(cpu.cycles, 8 + 12);
+    }
+
+    #[test]
+    fn stack_pointer_wraps() {
+        // PUSH BC ; POP BC with SP at 0000
+        let mut cpu = cpu_with_program(&[0xC5, 0xC1]);
+        cpu.sp = 0x0000;
+        cpu.step(0).unwrap();
+        assert_eq!(cpu.sp, 0xFFFE);
+        cpu.step(1).unwrap();
+        assert_eq!(cpu.sp, 0x0000);
+    }
+
+    #[test]
+    fn shadow_stack_follows_calls_and_returns() {
+        // C000: CALL C010 / C010: CALL C020 / C020: RET
+        let mut cpu = cpu_with_program(&[0xCD, 0x10, 0xC0]);
+        cpu.bus.write_byte(0xC010, 0xCD);
+        cpu.bus.write_byte(0xC011, 0x20);
+        cpu.bus.write_byte(0xC012, 0xC0);
+        cpu.bus.write_byte(0xC020, 0xC9);
+
+        cpu.step(0).unwrap();
+        cpu.step(1).unwrap();
+        assert_eq!(cpu.call_stack.frames().len(), 2);
+        assert_eq!(
+            cpu.backtrace(),
+            "  #0  C020\n  #1  C013  (return from call C020)\n  #2  C003  (return from call C010)\n"
+        );
+
+        cpu.step(2).unwrap();
+        assert_eq!(cpu.pc, 0xC013);
+        assert_eq!(cpu.call_stack.frames()[0].target, 0xC010);
+        assert_eq!(cpu.call_stack.frames().len(), 1);
+    }
+}
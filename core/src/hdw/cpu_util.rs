@@ -25,10 +25,9 @@ pub fn match_jump(cpu: &mut CPU, test: JumpTest) -> bool {
     let jump_condition = match test {
         JumpTest::NotZero => !cpu.registers.f.zero,
         JumpTest::NotCarry => !cpu.registers.f.carry,
-        JumpTest::Zero => !cpu.registers.f.zero,
-        JumpTest::Carry => !cpu.registers.f.carry,
-        JumpTest::Always => true,
-        JumpTest::HL => panic!("HL BAD"),
+        JumpTest::Zero => cpu.registers.f.zero,
+        JumpTest::Carry => cpu.registers.f.carry,
+        JumpTest::Always | JumpTest::HL => true,
     };
     jump_condition
 }
@@ -43,11 +42,62 @@ pub fn match_hl(cpu: &mut CPU, target: HLTarget) -> u8 {
         HLTarget::E => cpu.registers.e,
         HLTarget::H => cpu.registers.h,
         HLTarget::L => cpu.registers.l,
-        HLTarget::HL => cpu.bus.read_byte(None, cpu.registers.get_hl()),
+        HLTarget::HL => cpu.bus.read_byte(cpu.registers.get_hl()),
     };
     reg_target
 }
 
+// Method to write back to a HL Target
+pub fn set_hl_target(cpu: &mut CPU, target: HLTarget, value: u8) {
+    match target {
+        HLTarget::A => cpu.registers.a = value,
+        HLTarget::B => cpu.registers.b = value,
+        HLTarget::C => cpu.registers.c = value,
+        HLTarget::D => cpu.registers.d = value,
+        HLTarget::E => cpu.registers.e = value,
+        HLTarget::H => cpu.registers.h = value,
+        HLTarget::L => cpu.registers.l = value,
+        HLTarget::HL => cpu.bus.write_byte(cpu.registers.get_hl(), value),
+    }
+}
+
+// Method to match a BIT/RES/SET Target to its bit number and register
+pub fn match_bit(target: ByteTarget) -> (u8, HLTarget) {
+    match target {
+        ByteTarget::Zero(hl_target) => (0, hl_target),
+        ByteTarget::One(hl_target) => (1, hl_target),
+        ByteTarget::Two(hl_target) => (2, hl_target),
+        ByteTarget::Three(hl_target) => (3, hl_target),
+        ByteTarget::Four(hl_target) => (4, hl_target),
+        ByteTarget::Five(hl_target) => (5, hl_target),
+        ByteTarget::Six(hl_target) => (6, hl_target),
+        ByteTarget::Seven(hl_target) => (7, hl_target),
+    }
+}
+
+// Method to match an 8-bit ALU operand
+pub fn match_op(cpu: &mut CPU, target: OPTarget) -> u8 {
+    match target {
+        OPTarget::A => cpu.registers.a,
+        OPTarget::B => cpu.registers.b,
+        OPTarget::C => cpu.registers.c,
+        OPTarget::D => cpu.registers.d,
+        OPTarget::E => cpu.registers.e,
+        OPTarget::H => cpu.registers.h,
+        OPTarget::L => cpu.registers.l,
+        OPTarget::HL => cpu.bus.read_byte(cpu.registers.get_hl()),
+        OPTarget::D8 => cpu.bus.read_byte(cpu.pc.wrapping_add(1)),
+    }
+}
+
+// Function to get the length of an 8-bit ALU instruction
+pub fn op_length(target: OPTarget) -> u16 {
+    match target {
+        OPTarget::D8 => 2,
+        _ => 1,
+    }
+}
+
 // INC FLAGS [0x04, 0x14, 0x24, 0x34, 0x0C, 0x1C, 0x2C, 0x3C]
 pub fn set_flags_after_inc(cpu: &mut CPU, result: u8) {
     // [Z 0 H -]
@@ -66,16 +116,23 @@ pub fn set_flags_after_dec(cpu: &mut CPU, result: u8, original_value: u8) {
 }
 
 // ADC FLAGS [0x88, 0x89, 0x8A, 0x8B, 0x8C, 0x8D, 0x8E, 0x8F, 0xCE]
-pub fn set_flags_after_adc(cpu: &mut CPU, result: u8, original_value: u8, immediate_operand: u8) {
+pub fn set_flags_after_adc(
+    cpu: &mut CPU,
+    result: u8,
+    original_value: u8,
+    immediate_operand: u8,
+    carry: u8,
+) {
     // [Z 0 H CY]
     cpu.registers.f.zero = result == 0; // Zero Flag: Set if the result is zero
     cpu.registers.f.subtract = false; // Subtract Flag: SET (ADC is not a subtraction)
-    cpu.registers.f.half_carry = ((original_value & 0x0F) + (immediate_operand & 0x0F)) > 0x0F; // Half-Carry Flag: Set if there was a carry from bit 4 to bit 3
-    cpu.registers.f.carry = (result < original_value) || (result < immediate_operand);
+    cpu.registers.f.half_carry =
+        (original_value & 0x0F) + (immediate_operand & 0x0F) + carry > 0x0F; // Half-Carry Flag: Set if there was a carry from bit 3 to bit 4
+    cpu.registers.f.carry = original_value as u16 + immediate_operand as u16 + carry as u16 > 0xFF;
     // ^^ Carry Flag: Set if there was a carry from the 8th bit
 }
 
-// SUB SBC FLAGS [0x90, 0x91, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0xD6, 0x98, 0x99, 0x9A, 0x9B, 0x9C, 0x9D, 0x9E, 0x9F, 0xDE]
+// SUB FLAGS [0x90, 0x91, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0xD6]
 pub fn set_flags_after_sub(cpu: &mut CPU, result: u8, original_value: u8, immediate_operand: u8) {
     // [Z 1 H CY]
     cpu.registers.f.zero = result == 0; // Zero Flag
@@ -84,6 +141,22 @@ pub fn set_flags_after_sub(cpu: &mut CPU, result: u8, original_value: u8, immedi
     cpu.registers.f.carry = original_value < immediate_operand; // Carry Flag
 }
 
+// SBC FLAGS [0x98, 0x99, 0x9A, 0x9B, 0x9C, 0x9D, 0x9E, 0x9F, 0xDE]
+pub fn set_flags_after_sbc(
+    cpu: &mut CPU,
+    result: u8,
+    original_value: u8,
+    immediate_operand: u8,
+    carry: u8,
+) {
+    // [Z 1 H CY]
+    cpu.registers.f.zero = result == 0; // Zero Flag
+    cpu.registers.f.subtract = true; // Subtract Flag Always set because we SUB
+    cpu.registers.f.half_carry = (original_value & 0x0F) < (immediate_operand & 0x0F) + carry; // Half-Carry Flag: Borrow from bit 4 including the carry
+    cpu.registers.f.carry = (original_value as u16) < immediate_operand as u16 + carry as u16;
+    // Carry Flag: Borrow including the carry
+}
+
 // AND FLAGS [0xA0, 0xA1, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6, 0xA7, 0xE6]
 pub fn set_flags_after_and(cpu: &mut CPU, result: u8) {
     // [Z 0 1 0]
@@ -174,65 +247,46 @@ pub fn set_flags_after_no_pre_rl_rr(cpu: &mut CPU, bit: u8) {
 }
 
 // ADD A FLAGS [0x80, 0x81, 0x82, 0x83, 0x84, 0x85, 0x86, 0x87]
-pub fn set_flags_after_add_a(cpu: &mut CPU, reg_target: u8, original: u8, is_d8: bool) {
+pub fn set_flags_after_add_a(cpu: &mut CPU, reg_target: u8, original: u8) {
     // [Z 0 H CY]
-    if is_d8 {
-        cpu.registers.f.zero = cpu.registers.a == 0;
-        cpu.registers.f.subtract = false;
-        cpu.registers.f.half_carry = ((original & 0x0F) + (cpu.registers.a & 0x0F)) > 0x0F; // Half-Carry Flag: Set if carry from bit 3 to bit 4
-        cpu.registers.f.carry = (cpu.registers.a < original) || (cpu.registers.a < reg_target);
-        // ^^ Carry Flag: Set if carry out from the most significant bit
-    } else {
-        cpu.registers.f.zero = cpu.registers.a == 0; // Zero Flag: Set if the result is zero
-        cpu.registers.f.subtract = false; // Subtract Flag: Not set for ADD operations
-        cpu.registers.f.half_carry = (original & 0x0F) + (reg_target & 0x0F) > 0x0F; // Half-Carry Flag: Set if there was a carry from bit 3 to bit 4
-        cpu.registers.f.carry = cpu.registers.a < original; // Carry Flag: Set if the addition overflowed an 8-bit value
-    }
+    cpu.registers.f.zero = cpu.registers.a == 0; // Zero Flag: Set if the result is zero
+    cpu.registers.f.subtract = false; // Subtract Flag: Not set for ADD operations
+    cpu.registers.f.half_carry = (original & 0x0F) + (reg_target & 0x0F) > 0x0F; // Half-Carry Flag: Set if there was a carry from bit 3 to bit 4
+    cpu.registers.f.carry = cpu.registers.a < original; // Carry Flag: Set if the addition overflowed an 8-bit value
 }
 
 // ADD SP FLAGS [0xE8]
-pub fn set_flags_after_add_sp(cpu: &mut CPU, signed_value: i16) {
+// LD HL SP+E8 FLAGS [0xF8]
+pub fn set_flags_after_add_sp(cpu: &mut CPU, original_sp: u16, offset: u8) {
     // [0 0 H CY]
-    cpu.registers.f.zero = cpu.sp == 0; // zero
-    cpu.registers.f.subtract = false; // subtract
-    cpu.registers.f.carry = (cpu.sp as i16) < (signed_value as i16); // Carry Flag: Check if there's a carry out (would occur if SP > 0xFFFF)
-    cpu.registers.f.half_carry = ((cpu.sp & 0x0F) as i16 + (signed_value & 0x0F) as i16) > 0x0F;
-    // Half-Carry Flag: Check if there's a carry from bit 11 to bit 12 this check is done based on the lower 4 bits
+    cpu.registers.f.zero = false; // Zero Flag: Always cleared
+    cpu.registers.f.subtract = false; // Subtract Flag: Always cleared
+    cpu.registers.f.half_carry = (original_sp & 0x0F) + (offset as u16 & 0x0F) > 0x0F; // Half-Carry Flag: Unsigned carry from bit 3 of the low byte
+    cpu.registers.f.carry = (original_sp & 0xFF) + (offset as u16) > 0xFF; // Carry Flag: Unsigned carry from bit 7 of the low byte
 }
 
 // ADD N16 FLAGS [0x09, 0x19, 0x29, 0x39]
-pub fn set_flags_after_add_n16(cpu: &mut CPU, reg_target: u16) {
+pub fn set_flags_after_add_n16(cpu: &mut CPU, original_hl: u16, reg_target: u16) {
     // [- 0 H CY]
-    cpu.registers.f.carry = ((cpu.registers.get_hl() as u32) + (reg_target as u32)) > 0xFFFF; // Carry Flag: Check for carry from the addition
-    cpu.registers.f.half_carry =
-        ((cpu.registers.get_hl() & 0x0FFF) + (reg_target & 0x0FFF)) > 0x0FFF; // Half-Carry Flag: Check if there was a carry from bit 11 to bit 12
+    cpu.registers.f.carry = ((original_hl as u32) + (reg_target as u32)) > 0xFFFF; // Carry Flag: Check for carry from the addition
+    cpu.registers.f.half_carry = ((original_hl & 0x0FFF) + (reg_target & 0x0FFF)) > 0x0FFF; // Half-Carry Flag: Check if there was a carry from bit 11 to bit 12
     cpu.registers.f.subtract = false; // Subtract Flag: Not set for ADD operations
-    cpu.registers.f.zero = false; // Zero Flag: Not affected, but set to false
-}
-
-// LD SP FLAGS [0xF8]
-pub fn set_flags_after_ld_spe8(cpu: &mut CPU) {
-    cpu.registers.f.subtract = false;
-    cpu.registers.f.half_carry =
-        ((cpu.sp & 0x0F) + (cpu.bus.read_byte(None, cpu.pc + 1) as u16 & 0x0F)) > 0x0F;
-    cpu.registers.f.carry =
-        ((cpu.sp & 0xFF) + (cpu.bus.read_byte(None, cpu.pc + 1) as u16 & 0xFF)) > 0xFF;
 }
 
 pub fn set_int_flags(cpu: &mut CPU, value: u8) {
-    cpu.int_flags = value
+    cpu.bus.int_flags = value
 }
 
 pub fn get_int_flags(cpu: &mut CPU) -> u8 {
-    cpu.int_flags
+    cpu.bus.int_flags
 }
 
 // Function to help streamline alot of jumping instructions
 pub fn goto_addr(cpu: &mut CPU, address: u16, jump: bool, push_pc: bool) -> u16 {
     if jump {
         if push_pc {
-            // cycle 2
-            stack_push16(cpu, cpu.pc);
+            // cycle 2 -> return to the instruction after the CALL
+            stack_push16(cpu, cpu.pc.wrapping_add(3));
         }
         // combine and set pc to 2 byte addr in lil endian
         cpu.pc = address;
@@ -0,0 +1,168 @@
+/*
+
+    Crash Reports
+
+    A panic inside the core is caught at the frame boundary, the machine state is written to
+    crashes/crash-<unix time>-<rom>.txt and emulation stops with an error instead of taking the whole
+    process down
+
+    Report contents:
+        ROM name, panic message and location
+        PC, current opcode, registers, SP and IME
+        A backtrace from the shadow call stack
+        The newest hardware events when --events is logging them
+        The last HISTORY_LENGTH executed instructions (oldest first)
+
+*/
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::hdw::cpu::CPU;
+use crate::hdw::state::write_file_atomic;
+
+pub const CRASH_DIR: &str = "crashes";
+
+thread_local! {
+    // Message and location of the last panic on this thread, filled in by the hook
+    // Per thread so one emulator instance crashing can't label another's report
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// Function to install a panic hook that remembers where the panic happened
+// The default hook still runs so the message is printed as usual
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let location = info
+            .location()
+            .map(|location| format!(" at {}:{}", location.file(), location.line()))
+            .unwrap_or_default();
+        let message = format!("{}{}", panic_message(info.payload()), location);
+        LAST_PANIC.with(|last_panic| *last_panic.borrow_mut() = Some(message));
+        default_hook(info);
+    }));
+}
+
+// Function to get the text out of a panic payload
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("Unknown panic")
+    }
+}
+
+// Function to take the message the hook recorded (falls back to the payload without the hook)
+pub fn take_panic_message(payload: &(dyn Any + Send)) -> String {
+    LAST_PANIC
+        .with(|last_panic| last_panic.borrow_mut().take())
+        .unwrap_or_else(|| panic_message(payload))
+}
+
+// Function to build the crash report text
+pub fn crash_report(cpu: &CPU, message: &str) -> String {
+    let r = &cpu.registers;
+    let mut report = String::new();
+
+    // Writing to a String can't fail
+    let _ = writeln!(report, "ROM     : {}", cpu.bus.cart.file_name());
+    let _ = writeln!(report, "Panic   : {}", message);
+    let _ = writeln!(
+        report,
+        "PC      : {:04X} (opcode {:02X})",
+        cpu.pc, cpu.curr_opcode
+    );
+    let _ = writeln!(
+        report,
+        "AF: {:04X} BC: {:04X} DE: {:04X} HL: {:04X} SP: {:04X} IME: {}",
+        r.get_af(),
+        r.get_bc(),
+        r.get_de(),
+        r.get_hl(),
+        cpu.sp,
+        cpu.master_enabled as u8
+    );
+    let _ = writeln!(report, "Cycles  : {}", cpu.cycles);
+
+    let _ = writeln!(report, "\nBacktrace:\n{}", cpu.backtrace().trim_end());
+
+    if let Some(events) = &cpu.bus.events {
+        let _ = writeln!(
+            report,
+            "\nRecent Events:\n{}",
+            events.timeline(32).trim_end()
+        );
+    }
+
+    let _ = writeln!(report, "\nLast Instructions:");
+    for (pc, opcode) in cpu.recent_instructions() {
+        match cpu.symbols.label(pc, cpu.bus.cart.rom_bank()) {
+            Some(label) => {
+                let _ = writeln!(report, "  {:04X}: {:02X}  ; {}", pc, opcode, label);
+            }
+            None => {
+                let _ = writeln!(report, "  {:04X}: {:02X}", pc, opcode);
+            }
+        }
+    }
+    report
+}
+
+// Function to write a crash report into dir, returns the file it went to
+// The ROM name is part of the file name so instances crashing together don't collide
+pub fn write_crash_report(dir: &Path, rom_name: &str, report: &str) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create: {}. Error: {}", dir.display(), e))?;
+
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let rom_name = Path::new(rom_name)
+        .file_stem()
+        .map_or(String::from("none"), |stem| {
+            stem.to_string_lossy().into_owned()
+        });
+    let path = dir.join(format!("crash-{}-{}.txt", time, rom_name));
+    write_file_atomic(&path, report.as_bytes())?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdw::bus::Bus;
+    use crate::hdw::cart::Cartridge;
+    use crate::hdw::cpu::HISTORY_LENGTH as HISTORY;
+
+    #[test]
+    fn report_lists_recent_instructions() {
+        let mut cpu = CPU::new(Bus::new(Cartridge::new()));
+        cpu.pc = 0xC000;
+        for _ in 0..HISTORY + 2 {
+            cpu.bus.write_byte(cpu.pc, 0x00);
+            cpu.step(0).unwrap();
+        }
+        let recent: Vec<_> = cpu.recent_instructions().collect();
+        assert_eq!(recent.len(), HISTORY);
+        assert_eq!(recent[0], (0xC002, 0x00));
+        assert_eq!(recent[HISTORY - 1], (0xC000 + HISTORY as u16 + 1, 0x00));
+
+        let report = crash_report(&cpu, "boom");
+        assert!(report.contains("Panic   : boom"));
+        assert!(report.contains(&format!("PC      : {:04X}", cpu.pc)));
+        assert_eq!(
+            report
+                .lines()
+                .filter(|line| line.starts_with("  C0"))
+                .count(),
+            HISTORY
+        );
+    }
+}
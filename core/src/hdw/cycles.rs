@@ -0,0 +1,197 @@
+/*
+
+    Instruction Timing
+
+    M-cycles per opcode (1 M-cycle = 4 T-cycles)
+    Conditional jumps/calls/returns are listed with their not taken timing,
+    the CPU adds branch_taken_cycles when the condition passes
+
+    This is the only place instruction timing lives, ops never charge cycles themselves
+    Interrupt dispatch isn't an instruction, handle_interrupts charges its 5 M-cycles
+
+*/
+
+// Unprefixed Opcodes, 0 = Not an instruction
+#[rustfmt::skip]
+pub const OPCODE_CYCLES: [u8; 256] = [
+//  x0 x1 x2 x3 x4 x5 x6 x7 x8 x9 xA xB xC xD xE xF
+    1, 3, 2, 2, 1, 1, 2, 1, 5, 2, 2, 2, 1, 1, 2, 1, // 0x
+    1, 3, 2, 2, 1, 1, 2, 1, 3, 2, 2, 2, 1, 1, 2, 1, // 1x
+    2, 3, 2, 2, 1, 1, 2, 1, 2, 2, 2, 2, 1, 1, 2, 1, // 2x
+    2, 3, 2, 2, 3, 3, 3, 1, 2, 2, 2, 2, 1, 1, 2, 1, // 3x
+    1, 1, 1, 1, 1, 1, 2, 1, 1, 1, 1, 1, 1, 1, 2, 1, // 4x
+    1, 1, 1, 1, 1, 1, 2, 1, 1, 1, 1, 1, 1, 1, 2, 1, // 5x
+    1, 1, 1, 1, 1, 1, 2, 1, 1, 1, 1, 1, 1, 1, 2, 1, // 6x
+    2, 2, 2, 2, 2, 2, 1, 2, 1, 1, 1, 1, 1, 1, 2, 1, // 7x
+    1, 1, 1, 1, 1, 1, 2, 1, 1, 1, 1, 1, 1, 1, 2, 1, // 8x
+    1, 1, 1, 1, 1, 1, 2, 1, 1, 1, 1, 1, 1, 1, 2, 1, // 9x
+    1, 1, 1, 1, 1, 1, 2, 1, 1, 1, 1, 1, 1, 1, 2, 1, // Ax
+    1, 1, 1, 1, 1, 1, 2, 1, 1, 1, 1, 1, 1, 1, 2, 1, // Bx
+    2, 3, 3, 4, 3, 4, 2, 4, 2, 4, 3, 0, 3, 6, 2, 4, // Cx
+    2, 3, 3, 0, 3, 4, 2, 4, 2, 4, 3, 0, 3, 0, 2, 4, // Dx
+    3, 3, 2, 0, 0, 4, 2, 4, 4, 1, 4, 0, 0, 0, 2, 4, // Ex
+    3, 3, 2, 1, 0, 4, 2, 4, 3, 2, 4, 1, 0, 0, 2, 4, // Fx
+];
+
+// Function to get the extra M-cycles a conditional branch costs when taken
+pub fn branch_taken_cycles(opcode: u8) -> u8 {
+    match opcode {
+        // JR cc / JP cc -> 1 extra to load PC
+        0x20 | 0x28 | 0x30 | 0x38 | 0xC2 | 0xCA | 0xD2 | 0xDA => 1,
+        // CALL cc / RET cc -> 3 extra for the stack access and PC load
+        0xC4 | 0xCC | 0xD4 | 0xDC | 0xC0 | 0xC8 | 0xD0 | 0xD8 => 3,
+        _ => 0,
+    }
+}
+
+// Function to check a conditional branch's condition (bits 3-4: NZ, Z, NC, C) against the flags
+pub fn branch_condition(opcode: u8, zero: bool, carry: bool) -> bool {
+    match (opcode >> 3) & 0x03 {
+        0 => !zero,
+        1 => zero,
+        2 => !carry,
+        _ => carry,
+    }
+}
+
+// Function to get the base M-cycles of an instruction
+// cb_opcode is the byte after the opcode and is only used for 0xCB prefixed instructions
+pub fn opcode_cycles(opcode: u8, cb_opcode: u8) -> u8 {
+    if opcode == 0xCB {
+        // Prefixed -> register ops take 2, (HL) ops take 4 except BIT which only reads
+        match (cb_opcode & 0x07, cb_opcode) {
+            (6, 0x40..=0x7F) => 3,
+            (6, _) => 4,
+            _ => 2,
+        }
+    } else {
+        OPCODE_CYCLES[opcode as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Total M-cycles with the branch taken or not
+    fn cycles(opcode: u8, taken: bool) -> u8 {
+        let extra = if taken {
+            branch_taken_cycles(opcode)
+        } else {
+            0
+        };
+        opcode_cycles(opcode, 0) + extra
+    }
+
+    #[test]
+    fn matches_pan_docs_groups() {
+        // 8-bit loads
+        for opcode in 0x40..=0x7F {
+            let uses_hl = opcode & 0x07 == 6 || (0x70..=0x77).contains(&opcode);
+            if opcode != 0x76 {
+                assert_eq!(
+                    cycles(opcode, false),
+                    if uses_hl { 2 } else { 1 },
+                    "{:02X}",
+                    opcode
+                );
+            }
+        }
+
+        // 8-bit ALU
+        for opcode in 0x80..=0xBF {
+            let expected = if opcode & 0x07 == 6 { 2 } else { 1 };
+            assert_eq!(cycles(opcode, false), expected, "{:02X}", opcode);
+        }
+        for opcode in [0xC6, 0xCE, 0xD6, 0xDE, 0xE6, 0xEE, 0xF6, 0xFE] {
+            assert_eq!(cycles(opcode, false), 2, "{:02X}", opcode);
+        }
+
+        // Stack
+        for opcode in [0xC1, 0xD1, 0xE1, 0xF1] {
+            assert_eq!(cycles(opcode, false), 3, "{:02X}", opcode);
+        }
+        for opcode in [0xC5, 0xD5, 0xE5, 0xF5] {
+            assert_eq!(cycles(opcode, false), 4, "{:02X}", opcode);
+        }
+        for opcode in [0xC7, 0xCF, 0xD7, 0xDF, 0xE7, 0xEF, 0xF7, 0xFF] {
+            assert_eq!(cycles(opcode, false), 4, "{:02X}", opcode);
+        }
+
+        // Misc
+        assert_eq!(cycles(0x08, false), 5); // LD (a16), SP
+        assert_eq!(cycles(0x34, false), 3); // INC (HL)
+        assert_eq!(cycles(0x36, false), 3); // LD (HL), n8
+        assert_eq!(cycles(0xE8, false), 4); // ADD SP, e8
+        assert_eq!(cycles(0xF8, false), 3); // LD HL, SP+e8
+        assert_eq!(cycles(0xE9, false), 1); // JP HL
+        assert_eq!(cycles(0xEA, false), 4); // LD (a16), A
+        assert_eq!(cycles(0xE0, false), 3); // LDH (a8), A
+        assert_eq!(cycles(0xE2, false), 2); // LD (C), A
+    }
+
+    #[test]
+    fn matches_pan_docs_branches() {
+        // Unconditional
+        assert_eq!(cycles(0x18, false), 3); // JR
+        assert_eq!(cycles(0xC3, false), 4); // JP
+        assert_eq!(cycles(0xCD, false), 6); // CALL
+        assert_eq!(cycles(0xC9, false), 4); // RET
+        assert_eq!(cycles(0xD9, false), 4); // RETI
+
+        // Conditional -> (not taken, taken)
+        for opcode in [0x20, 0x28, 0x30, 0x38] {
+            assert_eq!((cycles(opcode, false), cycles(opcode, true)), (2, 3));
+        }
+        for opcode in [0xC2, 0xCA, 0xD2, 0xDA] {
+            assert_eq!((cycles(opcode, false), cycles(opcode, true)), (3, 4));
+        }
+        for opcode in [0xC4, 0xCC, 0xD4, 0xDC] {
+            assert_eq!((cycles(opcode, false), cycles(opcode, true)), (3, 6));
+        }
+        for opcode in [0xC0, 0xC8, 0xD0, 0xD8] {
+            assert_eq!((cycles(opcode, false), cycles(opcode, true)), (2, 5));
+        }
+
+        // Conditions
+        assert!(branch_condition(0x20, false, true)); // NZ
+        assert!(branch_condition(0xC8, true, false)); // Z
+        assert!(branch_condition(0xD2, true, false)); // NC
+        assert!(branch_condition(0xDC, false, true)); // C
+        assert!(!branch_condition(0x28, false, true));
+    }
+
+    // Prefixed M-cycles as listed in the Pan Docs opcode table
+    #[rustfmt::skip]
+    const PREFIXED_CYCLES: [u8; 256] = [
+    //  x0 x1 x2 x3 x4 x5 x6 x7 x8 x9 xA xB xC xD xE xF
+        2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 2, 2, 4, 2, // 0x RLC/RRC
+        2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 2, 2, 4, 2, // 1x RL/RR
+        2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 2, 2, 4, 2, // 2x SLA/SRA
+        2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 2, 2, 4, 2, // 3x SWAP/SRL
+        2, 2, 2, 2, 2, 2, 3, 2, 2, 2, 2, 2, 2, 2, 3, 2, // 4x BIT
+        2, 2, 2, 2, 2, 2, 3, 2, 2, 2, 2, 2, 2, 2, 3, 2, // 5x BIT
+        2, 2, 2, 2, 2, 2, 3, 2, 2, 2, 2, 2, 2, 2, 3, 2, // 6x BIT
+        2, 2, 2, 2, 2, 2, 3, 2, 2, 2, 2, 2, 2, 2, 3, 2, // 7x BIT
+        2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 2, 2, 4, 2, // 8x RES
+        2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 2, 2, 4, 2, // 9x RES
+        2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 2, 2, 4, 2, // Ax RES
+        2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 2, 2, 4, 2, // Bx RES
+        2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 2, 2, 4, 2, // Cx SET
+        2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 2, 2, 4, 2, // Dx SET
+        2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 2, 2, 4, 2, // Ex SET
+        2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 2, 2, 4, 2, // Fx SET
+    ];
+
+    #[test]
+    fn matches_pan_docs_prefixed() {
+        for (cb_opcode, expected) in PREFIXED_CYCLES.iter().enumerate() {
+            assert_eq!(
+                opcode_cycles(0xCB, cb_opcode as u8),
+                *expected,
+                "CB {:02X}",
+                cb_opcode
+            );
+        }
+    }
+}
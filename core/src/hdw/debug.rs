@@ -0,0 +1,579 @@
+/*
+
+    Debugger Helpers
+
+    Watchpoints are address ranges the bus checks on every read/write
+    They either log the access or break (pause the emulator) when hit
+    The bus only checks them when at least one is registered
+
+    Breakpoints pause when PC reaches an address, optionally only when a condition holds
+        --break "40A2 if A==0x3C && [0xC345]>5"
+    Conditions use C style operators (|| && == != < <= > >= | & + - ! and parentheses)
+        Registers : A B C D E F H L AF BC DE HL SP PC, flags ZF NF HF CF (0 or 1)
+        Memory    : [expr] reads the byte at an address without side effects
+        Numbers   : 0x3C or $3C are hex, plain digits are decimal
+        Names     : symbols and IO registers (LCDC) stand for their address, so [wLives] reads it
+    A bare word in a condition is always a register, flag or name, never hex, so BC is the
+    register and C0 is an error -> write [0xC0] to read memory at 00C0
+    The breakpoint address itself (before "if") takes bare hex like a watchpoint does
+
+*/
+use std::fmt;
+
+use crate::hdw::cpu::CPU;
+use crate::hdw::io::{io_register_address, io_register_name};
+use crate::hdw::symbols::SymbolTable;
+
+// What to do when a watchpoint is hit
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WatchAction {
+    Log,
+    Break,
+}
+
+// Type of bus access
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WatchAccess {
+    Read,
+    Write,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Watchpoint {
+    pub start: u16,
+    pub end: u16,
+    pub on_read: bool,
+    pub on_write: bool,
+    pub action: WatchAction,
+}
+
+// Record of the access that triggered a watchpoint
+#[derive(Clone, Copy, Debug)]
+pub struct WatchHit {
+    pub address: u16,
+    pub value: u8,
+    pub access: WatchAccess,
+}
+
+impl Watchpoint {
+    // Function to check an access against this watchpoint
+    pub fn matches(&self, address: u16, access: WatchAccess) -> bool {
+        let in_range = address >= self.start && address <= self.end;
+        match access {
+            WatchAccess::Read => in_range && self.on_read,
+            WatchAccess::Write => in_range && self.on_write,
+        }
+    }
+
+    // Function to parse a watchpoint from "ADDR[-END][:r|w|rw][:log|break]"
+    // ADDR and END can be hex (0xFF40, $FF40, FF40), an IO register name (LCDC) or a symbol
+    pub fn parse(spec: &str, symbols: &SymbolTable) -> Result<Watchpoint, String> {
+        let mut parts = spec.split(':');
+
+        // Address Range
+        let range = parts.next().unwrap_or("");
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (parse_address(start, symbols)?, parse_address(end, symbols)?),
+            None => {
+                let address = parse_address(range, symbols)?;
+                (address, address)
+            }
+        };
+        if start > end {
+            return Err(format!("Bad Watchpoint Range: {}", range));
+        }
+
+        // Defaults -> log on read and write
+        let mut watchpoint = Watchpoint {
+            start,
+            end,
+            on_read: true,
+            on_write: true,
+            action: WatchAction::Log,
+        };
+
+        // Options
+        for option in parts {
+            match option.to_ascii_lowercase().as_str() {
+                "r" => {
+                    watchpoint.on_read = true;
+                    watchpoint.on_write = false;
+                }
+                "w" => {
+                    watchpoint.on_read = false;
+                    watchpoint.on_write = true;
+                }
+                "rw" => {
+                    watchpoint.on_read = true;
+                    watchpoint.on_write = true;
+                }
+                "log" => watchpoint.action = WatchAction::Log,
+                "break" => watchpoint.action = WatchAction::Break,
+                _ => return Err(format!("Bad Watchpoint Option: {}", option)),
+            }
+        }
+
+        Ok(watchpoint)
+    }
+}
+
+// Registers and flags a condition can read
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Operand {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    H,
+    L,
+    AF,
+    BC,
+    DE,
+    HL,
+    SP,
+    PC,
+    ZeroFlag,
+    SubtractFlag,
+    HalfCarryFlag,
+    CarryFlag,
+}
+
+impl Operand {
+    // Function to look up a register or flag by name
+    fn from_name(name: &str) -> Option<Operand> {
+        Some(match name.to_ascii_uppercase().as_str() {
+            "A" => Operand::A,
+            "B" => Operand::B,
+            "C" => Operand::C,
+            "D" => Operand::D,
+            "E" => Operand::E,
+            "F" => Operand::F,
+            "H" => Operand::H,
+            "L" => Operand::L,
+            "AF" => Operand::AF,
+            "BC" => Operand::BC,
+            "DE" => Operand::DE,
+            "HL" => Operand::HL,
+            "SP" => Operand::SP,
+            "PC" => Operand::PC,
+            "ZF" => Operand::ZeroFlag,
+            "NF" => Operand::SubtractFlag,
+            "HF" => Operand::HalfCarryFlag,
+            "CF" => Operand::CarryFlag,
+            _ => return None,
+        })
+    }
+
+    // Method to read the operand's current value
+    fn value(self, cpu: &CPU) -> i64 {
+        let registers = &cpu.registers;
+        let value = match self {
+            Operand::A => registers.a as u16,
+            Operand::B => registers.b as u16,
+            Operand::C => registers.c as u16,
+            Operand::D => registers.d as u16,
+            Operand::E => registers.e as u16,
+            Operand::F => registers.get_af() & 0xFF,
+            Operand::H => registers.h as u16,
+            Operand::L => registers.l as u16,
+            Operand::AF => registers.get_af(),
+            Operand::BC => registers.get_bc(),
+            Operand::DE => registers.get_de(),
+            Operand::HL => registers.get_hl(),
+            Operand::SP => cpu.sp,
+            Operand::PC => cpu.pc,
+            Operand::ZeroFlag => registers.f.zero as u16,
+            Operand::SubtractFlag => registers.f.subtract as u16,
+            Operand::HalfCarryFlag => registers.f.half_carry as u16,
+            Operand::CarryFlag => registers.f.carry as u16,
+        };
+        value as i64
+    }
+}
+
+// Parsed condition expression
+#[derive(Clone, PartialEq, Debug)]
+enum Expr {
+    Number(i64),
+    Operand(Operand),
+    Memory(Box<Expr>),
+    Not(Box<Expr>),
+    Negate(Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    // Method to evaluate against the CPU, comparisons and logic give 0 or 1
+    fn eval(&self, cpu: &CPU) -> i64 {
+        match self {
+            Expr::Number(value) => *value,
+            Expr::Operand(operand) => operand.value(cpu),
+            Expr::Memory(address) => cpu.bus.peek_byte(address.eval(cpu) as u16) as i64,
+            Expr::Not(expr) => (expr.eval(cpu) == 0) as i64,
+            Expr::Negate(expr) => expr.eval(cpu).wrapping_neg(),
+            Expr::Binary(op, left, right) => {
+                let left = left.eval(cpu);
+                // Short circuit so [..] reads stay cheap
+                match *op {
+                    "&&" => return (left != 0 && right.eval(cpu) != 0) as i64,
+                    "||" => return (left != 0 || right.eval(cpu) != 0) as i64,
+                    _ => {}
+                }
+                let right = right.eval(cpu);
+                match *op {
+                    "==" => (left == right) as i64,
+                    "!=" => (left != right) as i64,
+                    "<" => (left < right) as i64,
+                    "<=" => (left <= right) as i64,
+                    ">" => (left > right) as i64,
+                    ">=" => (left >= right) as i64,
+                    "|" => left | right,
+                    "&" => left & right,
+                    "+" => left.wrapping_add(right),
+                    _ => left.wrapping_sub(right),
+                }
+            }
+        }
+    }
+}
+
+// Condition tokens
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Number(i64),
+    Name(String),
+    Symbol(&'static str),
+}
+
+// Operators and brackets, two character ones first so they win
+const SYMBOLS: [&str; 17] = [
+    "==", "!=", "<=", ">=", "&&", "||", "<", ">", "|", "&", "+", "-", "!", "(", ")", "[", "]",
+];
+
+// Binary operators from loosest to tightest binding
+const PRECEDENCE: [&[&str]; 6] = [
+    &["||"],
+    &["&&"],
+    &["==", "!=", "<", "<=", ">", ">="],
+    &["|"],
+    &["&"],
+    &["+", "-"],
+];
+
+// Function to split a condition into tokens
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+
+    while let Some(c) = rest.chars().next() {
+        let length = if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol))
+        {
+            tokens.push(Token::Symbol(symbol));
+            symbol.len()
+        } else if c.is_ascii_digit() || c == '$' {
+            let length = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '$')
+                .unwrap_or(rest.len());
+            let number = &rest[..length];
+            let value = match number.strip_prefix("0x").or(number.strip_prefix("$")) {
+                Some(hex) => i64::from_str_radix(hex, 16),
+                None => number.parse(),
+            };
+            tokens.push(Token::Number(
+                value.map_err(|_| format!("Bad Number: {}", number))?,
+            ));
+            length
+        } else if c.is_ascii_alphabetic() || c == '_' || c == '.' {
+            let length = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '.')
+                .unwrap_or(rest.len());
+            tokens.push(Token::Name(rest[..length].to_string()));
+            length
+        } else {
+            return Err(format!("Bad Condition Character: {}", c));
+        };
+        rest = rest[length..].trim_start();
+    }
+    Ok(tokens)
+}
+
+// Recursive descent parser over the tokens
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    symbols: &'a SymbolTable,
+}
+
+impl Parser<'_> {
+    // Method to take the next token if it is one of some operators
+    fn eat(&mut self, symbols: &[&str]) -> Option<&'static str> {
+        match self.tokens.get(self.position) {
+            Some(Token::Symbol(symbol)) if symbols.contains(symbol) => {
+                self.position += 1;
+                Some(symbol)
+            }
+            _ => None,
+        }
+    }
+
+    // Method to require a closing bracket
+    fn expect(&mut self, symbol: &str) -> Result<(), String> {
+        self.eat(&[symbol])
+            .map(|_| ())
+            .ok_or(format!("Expected {}", symbol))
+    }
+
+    // Method to parse binary operators at a precedence level and tighter
+    fn binary(&mut self, level: usize) -> Result<Expr, String> {
+        let Some(operators) = PRECEDENCE.get(level) else {
+            return self.unary();
+        };
+        let mut left = self.binary(level + 1)?;
+        while let Some(op) = self.eat(operators) {
+            let right = self.binary(level + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    // Method to parse prefix operators, brackets and values
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat(&["!"]).is_some() {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat(&["-"]).is_some() {
+            return Ok(Expr::Negate(Box::new(self.unary()?)));
+        }
+        if self.eat(&["("]).is_some() {
+            let expr = self.binary(0)?;
+            self.expect(")")?;
+            return Ok(expr);
+        }
+        if self.eat(&["["]).is_some() {
+            let address = self.binary(0)?;
+            self.expect("]")?;
+            return Ok(Expr::Memory(Box::new(address)));
+        }
+
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        match token {
+            Some(Token::Number(value)) => Ok(Expr::Number(value)),
+            Some(Token::Name(name)) => match Operand::from_name(&name) {
+                Some(operand) => Ok(Expr::Operand(operand)),
+                None => parse_name(&name, self.symbols)
+                    .map(|address| Expr::Number(address as i64))
+                    .ok_or(format!("Unknown Name: {} (hex needs 0x or $)", name)),
+            },
+            Some(Token::Symbol(symbol)) => Err(format!("Unexpected {}", symbol)),
+            None => Err(String::from("Condition Ends Early")),
+        }
+    }
+}
+
+// Breakpoint condition, keeps its text for messages
+#[derive(Clone, PartialEq, Debug)]
+pub struct Condition {
+    expr: Expr,
+    text: String,
+}
+
+impl Condition {
+    // Function to parse a condition like "A==0x3C && [0xC345]>5"
+    pub fn parse(text: &str, symbols: &SymbolTable) -> Result<Condition, String> {
+        let text = text.trim();
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            position: 0,
+            symbols,
+        };
+        let expr = parser
+            .binary(0)
+            .map_err(|e| format!("Bad Condition: {}. Error: {}", text, e))?;
+        if parser.position < parser.tokens.len() {
+            return Err(format!("Bad Condition: {}. Error: Trailing input", text));
+        }
+        Ok(Condition {
+            expr,
+            text: text.to_string(),
+        })
+    }
+
+    // Method to check the condition against the CPU
+    pub fn holds(&self, cpu: &CPU) -> bool {
+        self.expr.eval(cpu) != 0
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Breakpoint {
+    pub address: u16,
+    pub condition: Option<Condition>,
+}
+
+impl Breakpoint {
+    // Function to parse a breakpoint from "ADDR [if CONDITION]"
+    // ADDR takes the same forms as a watchpoint address
+    pub fn parse(spec: &str, symbols: &SymbolTable) -> Result<Breakpoint, String> {
+        let spec = spec.trim();
+        let (address, condition) = match spec.split_once(char::is_whitespace) {
+            Some((address, rest)) => {
+                let condition = rest
+                    .trim_start()
+                    .strip_prefix("if")
+                    .filter(|condition| condition.starts_with(char::is_whitespace))
+                    .ok_or(format!("Bad Breakpoint: {}", spec))?;
+                (address, Some(Condition::parse(condition, symbols)?))
+            }
+            None => (spec, None),
+        };
+        Ok(Breakpoint {
+            address: parse_address(address, symbols)?,
+            condition,
+        })
+    }
+
+    // Method to check if the CPU should break here
+    pub fn hits(&self, cpu: &CPU) -> bool {
+        cpu.pc == self.address
+            && self
+                .condition
+                .as_ref()
+                .is_none_or(|condition| condition.holds(cpu))
+    }
+}
+
+// Function to look up a symbol or IO register name
+fn parse_name(text: &str, symbols: &SymbolTable) -> Option<u16> {
+    symbols.address(text).or_else(|| io_register_address(text))
+}
+
+// Function to parse a hex address, IO register name or symbol
+pub fn parse_address(text: &str, symbols: &SymbolTable) -> Result<u16, String> {
+    if let Some(address) = parse_name(text, symbols) {
+        return Ok(address);
+    }
+
+    let hex = text
+        .trim_start_matches("0x")
+        .trim_start_matches("0X")
+        .trim_start_matches('$');
+    u16::from_str_radix(hex, 16).map_err(|_| format!("Bad Address: {}", text))
+}
+
+impl fmt::Display for WatchHit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let access = match self.access {
+            WatchAccess::Read => "READ",
+            WatchAccess::Write => "WRITE",
+        };
+        write!(
+            f,
+            "WATCH: {} {:02X} @ {:04X}",
+            access, self.value, self.address
+        )?;
+        if let Some(name) = io_register_name(self.address) {
+            write!(f, " ({})", name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdw::bus::Bus;
+    use crate::hdw::cart::Cartridge;
+
+    #[test]
+    fn parses_watchpoint_specs() {
+        let symbols = SymbolTable::new();
+        let watchpoint = Watchpoint::parse("C000", &symbols).unwrap();
+        assert_eq!((watchpoint.start, watchpoint.end), (0xC000, 0xC000));
+        assert!(watchpoint.on_read && watchpoint.on_write);
+        assert_eq!(watchpoint.action, WatchAction::Log);
+
+        let watchpoint = Watchpoint::parse("$C000-0xC0FF:w:break", &symbols).unwrap();
+        assert_eq!((watchpoint.start, watchpoint.end), (0xC000, 0xC0FF));
+        assert!(!watchpoint.on_read && watchpoint.on_write);
+        assert_eq!(watchpoint.action, WatchAction::Break);
+
+        let watchpoint = Watchpoint::parse("LCDC:R:LOG", &symbols).unwrap();
+        assert_eq!((watchpoint.start, watchpoint.end), (0xFF40, 0xFF40));
+        assert!(watchpoint.on_read && !watchpoint.on_write);
+
+        for bad in ["", "C0FF-C000", "C000:x", "C000:w:stop", "nowhere", "C000-"] {
+            assert!(Watchpoint::parse(bad, &symbols).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn watchpoints_match_range_and_access() {
+        let watchpoint = Watchpoint::parse("C000-C00F:w", &SymbolTable::new()).unwrap();
+        assert!(watchpoint.matches(0xC000, WatchAccess::Write));
+        assert!(watchpoint.matches(0xC00F, WatchAccess::Write));
+        assert!(!watchpoint.matches(0xBFFF, WatchAccess::Write));
+        assert!(!watchpoint.matches(0xC010, WatchAccess::Write));
+        assert!(!watchpoint.matches(0xC008, WatchAccess::Read));
+    }
+
+    #[test]
+    fn evaluates_breakpoint_conditions() {
+        let mut cpu = CPU::new(Bus::new(Cartridge::new()));
+        cpu.pc = 0x40A2;
+        cpu.registers.a = 0x3C;
+        cpu.registers.set_hl(0xC345);
+        cpu.registers.f.carry = true;
+        cpu.bus.write_byte(0xC345, 6);
+
+        let symbols = SymbolTable::new();
+        let holds = |text: &str| Condition::parse(text, &symbols).unwrap().holds(&cpu);
+        assert!(holds("A==0x3C && [0xC345]>5"));
+        assert!(!holds("A == $3C && [HL] > 6"));
+        assert!(holds("[HL] >= 6 || B"));
+        assert!(holds("CF && !ZF"));
+        assert!(holds("(A & 0x0F) == 12 && HL - 0xC340 == 5"));
+        assert!(holds("[HL+1-1] == 6 && -1 < 0"));
+        assert!(holds("[LCDC] == [0xFF40]"));
+
+        for bad in ["", "A ==", "A == 3)", "[HL", "A # 3", "nowhere > 1", "0xZZ"] {
+            assert!(Condition::parse(bad, &symbols).is_err(), "{}", bad);
+        }
+
+        let breakpoint = Breakpoint::parse("40A2 if A==0x3C", &symbols).unwrap();
+        assert!(breakpoint.hits(&cpu));
+        assert_eq!(breakpoint.condition.unwrap().to_string(), "A==0x3C");
+        assert!(!Breakpoint::parse("40A2 if A==0", &symbols)
+            .unwrap()
+            .hits(&cpu));
+        assert!(Breakpoint::parse("$40A2", &symbols).unwrap().hits(&cpu));
+        assert!(Breakpoint::parse("40A2 when A", &symbols).is_err());
+    }
+
+    #[test]
+    fn condition_words_are_registers_or_names_not_hex() {
+        let mut cpu = CPU::new(Bus::new(Cartridge::new()));
+        cpu.registers.set_bc(10);
+        cpu.bus.write_byte(0xFFC0, 10);
+        let symbols = SymbolTable::new();
+        let holds = |text: &str| Condition::parse(text, &symbols).unwrap().holds(&cpu);
+
+        // Register names win, and plain digits are decimal
+        assert!(holds("BC == 10"));
+        assert!(holds("C == 0x0A"));
+
+        // Hex needs a prefix, bare hex words are rejected instead of read as addresses
+        assert!(holds("[0xFFC0] == 10 && [$FFC0] == 0x0A"));
+        for bad in ["C0 == 10", "[FFC0] == 10", "FF"] {
+            assert!(Condition::parse(bad, &symbols).is_err(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,472 @@
+/*
+
+    Whole Cartridge Disassembler
+
+    Writes one RGBDS style bank_XXX.asm file per ROM bank
+
+    Code/Data Separation:
+    - Code is found by following control flow from the entry point, RST and interrupt vectors
+    - Jumps into 0x4000 - 0x7FFF from bank 0 are followed into bank 1 (the bank mapped at power on)
+      other banks are only followed from references inside themselves
+    - With a --cdl log, every byte the game executed is traced as code too (reaching banks
+      control flow can't) and bytes only ever read as data are never decoded
+    - Anything never reached is written out as db data
+    - Labels landing inside an instruction are defined relative to it (DEF label EQU @-n)
+
+*/
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use lazy_static::lazy_static;
+
+use crate::hdw::cdl::{CodeDataLog, CDL_CODE, CDL_DATA};
+use crate::hdw::symbols::SymbolTable;
+
+const BANK_SIZE: usize = 0x4000;
+
+// Unprefixed opcode templates, "" = Not an instruction (or the 0xCB prefix)
+// n8/n16 = immediate, a8/a16 = address, r8 = relative jump, e8 = signed offset
+#[rustfmt::skip]
+const MNEMONICS: [&str; 256] = [
+    // 0x
+    "nop", "ld bc, n16", "ld [bc], a", "inc bc", "inc b", "dec b", "ld b, n8", "rlca",
+    "ld [a16], sp", "add hl, bc", "ld a, [bc]", "dec bc", "inc c", "dec c", "ld c, n8", "rrca",
+    // 1x
+    "stop", "ld de, n16", "ld [de], a", "inc de", "inc d", "dec d", "ld d, n8", "rla",
+    "jr r8", "add hl, de", "ld a, [de]", "dec de", "inc e", "dec e", "ld e, n8", "rra",
+    // 2x
+    "jr nz, r8", "ld hl, n16", "ld [hli], a", "inc hl", "inc h", "dec h", "ld h, n8", "daa",
+    "jr z, r8", "add hl, hl", "ld a, [hli]", "dec hl", "inc l", "dec l", "ld l, n8", "cpl",
+    // 3x
+    "jr nc, r8", "ld sp, n16", "ld [hld], a", "inc sp", "inc [hl]", "dec [hl]", "ld [hl], n8", "scf",
+    "jr c, r8", "add hl, sp", "ld a, [hld]", "dec sp", "inc a", "dec a", "ld a, n8", "ccf",
+    // 4x
+    "ld b, b", "ld b, c", "ld b, d", "ld b, e", "ld b, h", "ld b, l", "ld b, [hl]", "ld b, a",
+    "ld c, b", "ld c, c", "ld c, d", "ld c, e", "ld c, h", "ld c, l", "ld c, [hl]", "ld c, a",
+    // 5x
+    "ld d, b", "ld d, c", "ld d, d", "ld d, e", "ld d, h", "ld d, l", "ld d, [hl]", "ld d, a",
+    "ld e, b", "ld e, c", "ld e, d", "ld e, e", "ld e, h", "ld e, l", "ld e, [hl]", "ld e, a",
+    // 6x
+    "ld h, b", "ld h, c", "ld h, d", "ld h, e", "ld h, h", "ld h, l", "ld h, [hl]", "ld h, a",
+    "ld l, b", "ld l, c", "ld l, d", "ld l, e", "ld l, h", "ld l, l", "ld l, [hl]", "ld l, a",
+    // 7x
+    "ld [hl], b", "ld [hl], c", "ld [hl], d", "ld [hl], e", "ld [hl], h", "ld [hl], l", "halt", "ld [hl], a",
+    "ld a, b", "ld a, c", "ld a, d", "ld a, e", "ld a, h", "ld a, l", "ld a, [hl]", "ld a, a",
+    // 8x
+    "add a, b", "add a, c", "add a, d", "add a, e", "add a, h", "add a, l", "add a, [hl]", "add a, a",
+    "adc a, b", "adc a, c", "adc a, d", "adc a, e", "adc a, h", "adc a, l", "adc a, [hl]", "adc a, a",
+    // 9x
+    "sub a, b", "sub a, c", "sub a, d", "sub a, e", "sub a, h", "sub a, l", "sub a, [hl]", "sub a, a",
+    "sbc a, b", "sbc a, c", "sbc a, d", "sbc a, e", "sbc a, h", "sbc a, l", "sbc a, [hl]", "sbc a, a",
+    // Ax
+    "and a, b", "and a, c", "and a, d", "and a, e", "and a, h", "and a, l", "and a, [hl]", "and a, a",
+    "xor a, b", "xor a, c", "xor a, d", "xor a, e", "xor a, h", "xor a, l", "xor a, [hl]", "xor a, a",
+    // Bx
+    "or a, b", "or a, c", "or a, d", "or a, e", "or a, h", "or a, l", "or a, [hl]", "or a, a",
+    "cp a, b", "cp a, c", "cp a, d", "cp a, e", "cp a, h", "cp a, l", "cp a, [hl]", "cp a, a",
+    // Cx
+    "ret nz", "pop bc", "jp nz, a16", "jp a16", "call nz, a16", "push bc", "add a, n8", "rst $00",
+    "ret z", "ret", "jp z, a16", "", "call z, a16", "call a16", "adc a, n8", "rst $08",
+    // Dx
+    "ret nc", "pop de", "jp nc, a16", "", "call nc, a16", "push de", "sub a, n8", "rst $10",
+    "ret c", "reti", "jp c, a16", "", "call c, a16", "", "sbc a, n8", "rst $18",
+    // Ex
+    "ldh [a8], a", "pop hl", "ldh [c], a", "", "", "push hl", "and a, n8", "rst $20",
+    "add sp, e8", "jp hl", "ld [a16], a", "", "", "", "xor a, n8", "rst $28",
+    // Fx
+    "ldh a, [a8]", "pop af", "ldh a, [c]", "di", "", "push af", "or a, n8", "rst $30",
+    "ld hl, sp+e8", "ld sp, hl", "ld a, [a16]", "ei", "", "", "cp a, n8", "rst $38",
+];
+
+// Prefixed opcode names
+const CB_OPS: [&str; 8] = ["rlc", "rrc", "rl", "rr", "sla", "sra", "swap", "srl"];
+const CB_REGS: [&str; 8] = ["b", "c", "d", "e", "h", "l", "[hl]", "a"];
+
+lazy_static! {
+    // Lengths worked out once so the code/data logger can look them up every instruction
+    static ref INSTRUCTION_LENGTHS: [u8; 256] = {
+        let mut lengths = [0; 256];
+        for (opcode, length) in lengths.iter_mut().enumerate() {
+            *length = opcode_length(opcode as u8) as u8;
+        }
+        lengths
+    };
+}
+
+// Function to get the length of an instruction in bytes
+pub fn instruction_length(opcode: u8) -> usize {
+    INSTRUCTION_LENGTHS[opcode as usize] as usize
+}
+
+// Function to work out an instruction's length from its mnemonic
+fn opcode_length(opcode: u8) -> usize {
+    let template = MNEMONICS[opcode as usize];
+    if opcode == 0xCB || opcode == 0x10 {
+        // Prefixed instructions and STOP take a second byte
+        2
+    } else if template.contains("n16") || template.contains("a16") {
+        3
+    } else if ["n8", "a8", "r8", "e8"]
+        .iter()
+        .any(|arg| template.contains(arg))
+    {
+        2
+    } else {
+        1
+    }
+}
+
+// Function to convert a bank offset to the address it is mapped at
+fn bank_address(bank: usize, offset: usize) -> u16 {
+    if bank == 0 {
+        offset as u16
+    } else {
+        (BANK_SIZE + offset) as u16
+    }
+}
+
+struct Disassembler<'a> {
+    rom: &'a [u8],
+    symbols: &'a SymbolTable,
+    cdl: Option<&'a CodeDataLog>,
+    starts: Vec<bool>,
+    labels: HashSet<(usize, u16)>,
+}
+
+impl<'a> Disassembler<'a> {
+    fn new(rom: &'a [u8], symbols: &'a SymbolTable, cdl: Option<&'a CodeDataLog>) -> Self {
+        Disassembler {
+            rom,
+            symbols,
+            cdl,
+            starts: vec![false; rom.len()],
+            labels: HashSet::new(),
+        }
+    }
+
+    // Function to check if the CDL log only ever saw a byte read as data
+    fn is_logged_data(&self, offset: usize) -> bool {
+        self.cdl
+            .is_some_and(|cdl| cdl.flags(offset) & (CDL_CODE | CDL_DATA) == CDL_DATA)
+    }
+
+    // Function to find which ROM offset a jump target lands on
+    fn resolve_target(&self, bank: usize, target: u16) -> Option<(usize, usize)> {
+        let target_bank = match target {
+            0x0000..=0x3FFF => 0,
+            0x4000..=0x7FFF => bank.max(1),
+            // RAM/HRAM targets can't be followed statically
+            _ => return None,
+        };
+        let offset = target_bank * BANK_SIZE + (target as usize % BANK_SIZE);
+
+        if offset < self.rom.len() {
+            Some((target_bank, offset))
+        } else {
+            None
+        }
+    }
+
+    // Function to mark code reachable from a ROM offset
+    fn trace(&mut self, bank: usize, offset: usize) {
+        let mut pending = vec![(bank, offset)];
+
+        while let Some((bank, mut offset)) = pending.pop() {
+            let bank_end = (bank + 1) * BANK_SIZE;
+
+            loop {
+                // Stop at bank end, ROM end and code we've already seen
+                if offset >= bank_end.min(self.rom.len())
+                    || self.starts[offset]
+                    || self.is_logged_data(offset)
+                {
+                    break;
+                }
+
+                let opcode = self.rom[offset];
+                let length = instruction_length(opcode);
+                if (MNEMONICS[opcode as usize].is_empty() && opcode != 0xCB)
+                    || offset + length > bank_end.min(self.rom.len())
+                {
+                    break;
+                }
+                self.starts[offset] = true;
+
+                // Find any branch target
+                let address = bank_address(bank, offset % BANK_SIZE);
+                let target = match opcode {
+                    // JR
+                    0x18 | 0x20 | 0x28 | 0x30 | 0x38 => {
+                        let distance = self.rom[offset + 1] as i8;
+                        Some(address.wrapping_add(2).wrapping_add(distance as u16))
+                    }
+                    // JP/CALL
+                    0xC2 | 0xC3 | 0xCA | 0xD2 | 0xDA | 0xC4 | 0xCC | 0xCD | 0xD4 | 0xDC => {
+                        Some(u16::from_le_bytes([
+                            self.rom[offset + 1],
+                            self.rom[offset + 2],
+                        ]))
+                    }
+                    // RST
+                    0xC7 | 0xCF | 0xD7 | 0xDF | 0xE7 | 0xEF | 0xF7 | 0xFF => {
+                        Some((opcode & 0x38) as u16)
+                    }
+                    _ => None,
+                };
+                if let Some(target) = target {
+                    if let Some((target_bank, target_offset)) = self.resolve_target(bank, target) {
+                        self.labels.insert((target_bank, target));
+                        pending.push((target_bank, target_offset));
+                    }
+                }
+
+                // Unconditional JR/JP/RET/RETI/JP HL end the block
+                if matches!(opcode, 0x18 | 0xC3 | 0xC9 | 0xD9 | 0xE9) {
+                    break;
+                }
+                offset += length;
+            }
+        }
+    }
+
+    // Function to name an address in a bank
+    fn label_name(&self, bank: usize, address: u16) -> Option<String> {
+        if let Some(label) = self.symbols.label(address, bank.max(1) as u8) {
+            return Some(label.to_string());
+        }
+        if self.labels.contains(&(bank, address)) {
+            return Some(format!("L_{:03X}_{:04X}", bank, address));
+        }
+        None
+    }
+
+    // Function to format an address operand using a label when there is one
+    fn address_operand(&self, bank: usize, target: u16) -> String {
+        self.resolve_target(bank, target)
+            .and_then(|(target_bank, _)| self.label_name(target_bank, target))
+            .unwrap_or(format!("${:04X}", target))
+    }
+
+    // Function to format the instruction at a ROM offset
+    fn format_instruction(&self, bank: usize, offset: usize) -> String {
+        let opcode = self.rom[offset];
+        let address = bank_address(bank, offset % BANK_SIZE);
+
+        if opcode == 0xCB {
+            let cb_opcode = self.rom[offset + 1];
+            let reg = CB_REGS[(cb_opcode & 0x07) as usize];
+            let bit = (cb_opcode >> 3) & 0x07;
+            return match cb_opcode >> 6 {
+                0 => format!("{} {}", CB_OPS[bit as usize], reg),
+                1 => format!("bit {}, {}", bit, reg),
+                2 => format!("res {}, {}", bit, reg),
+                _ => format!("set {}, {}", bit, reg),
+            };
+        }
+
+        let template = MNEMONICS[opcode as usize];
+        let byte = *self.rom.get(offset + 1).unwrap_or(&0);
+        let word = u16::from_le_bytes([byte, *self.rom.get(offset + 2).unwrap_or(&0)]);
+
+        if template.contains("n16") {
+            template.replace("n16", &format!("${:04X}", word))
+        } else if template.contains("a16") {
+            template.replace("a16", &self.address_operand(bank, word))
+        } else if template.contains("n8") {
+            template.replace("n8", &format!("${:02X}", byte))
+        } else if template.contains("a8") {
+            template.replace("a8", &format!("$FF{:02X}", byte))
+        } else if template.contains("r8") {
+            let target = address.wrapping_add(2).wrapping_add(byte as i8 as u16);
+            template.replace("r8", &self.address_operand(bank, target))
+        } else if template.contains("e8") {
+            template.replace("e8", &format!("{}", byte as i8))
+        } else {
+            template.to_string()
+        }
+    }
+
+    // Function to write out a bank as text
+    fn write_bank(&self, bank: usize) -> String {
+        let mut output = if bank == 0 {
+            String::from("SECTION \"ROM Bank $000\", ROM0[$0000]\n")
+        } else {
+            format!(
+                "SECTION \"ROM Bank ${:03X}\", ROMX[$4000], BANK[${:X}]\n",
+                bank, bank
+            )
+        };
+
+        let bank_start = bank * BANK_SIZE;
+        let bank_end = (bank_start + BANK_SIZE).min(self.rom.len());
+        let mut offset = bank_start;
+        let mut data: Vec<u8> = Vec::new();
+
+        while offset < bank_end {
+            let address = bank_address(bank, offset - bank_start);
+            let label = self.label_name(bank, address);
+
+            // Flush data at labels, code and every 8 bytes
+            if !data.is_empty() && (label.is_some() || self.starts[offset] || data.len() == 8) {
+                output.push_str(&Self::format_data(&data));
+                data.clear();
+            }
+
+            if let Some(label) = label {
+                output.push_str(&format!("\n{}:\n", label));
+            }
+
+            if self.starts[offset] {
+                let length = instruction_length(self.rom[offset]);
+                output.push_str(&format!("    {}\n", self.format_instruction(bank, offset)));
+
+                // Labels inside the instruction can't go on a line of their own
+                for inner in 1..length {
+                    if let Some(label) = self.label_name(bank, address + inner as u16) {
+                        output.push_str(&format!("DEF {} EQU @-{}\n", label, length - inner));
+                    }
+                }
+                offset += length;
+            } else {
+                data.push(self.rom[offset]);
+                offset += 1;
+            }
+        }
+        if !data.is_empty() {
+            output.push_str(&Self::format_data(&data));
+        }
+
+        output
+    }
+
+    fn format_data(data: &[u8]) -> String {
+        let bytes: Vec<String> = data.iter().map(|byte| format!("${:02X}", byte)).collect();
+        format!("    db {}\n", bytes.join(", "))
+    }
+}
+
+// Function to find the code in a ROM
+fn disassemble<'a>(
+    rom: &'a [u8],
+    symbols: &'a SymbolTable,
+    cdl: Option<&'a CodeDataLog>,
+) -> Disassembler<'a> {
+    let mut disassembler = Disassembler::new(rom, symbols, cdl);
+
+    // Entry point, RST vectors and interrupt vectors
+    let mut entry_points: Vec<u16> = vec![0x0100];
+    entry_points.extend((0x00..=0x38).step_by(8));
+    entry_points.extend([0x40, 0x48, 0x50, 0x58, 0x60]);
+    for address in entry_points {
+        if (address as usize) < rom.len() && rom[address as usize] != 0xFF {
+            disassembler.labels.insert((0, address));
+            disassembler.trace(0, address as usize);
+        }
+    }
+
+    // Trace anything else the CDL log saw executed, starting at the first byte of each run
+    if let Some(cdl) = cdl {
+        let mut covered_until = 0;
+        for (offset, &opcode) in rom.iter().enumerate().take(cdl.len()) {
+            if !disassembler.starts[offset]
+                && offset >= covered_until
+                && cdl.flags(offset) & CDL_CODE != 0
+            {
+                disassembler.trace(offset / BANK_SIZE, offset);
+            }
+            if disassembler.starts[offset] {
+                covered_until = covered_until.max(offset + instruction_length(opcode));
+            }
+        }
+    }
+
+    disassembler
+}
+
+// Function to disassemble a whole ROM into out_dir, returns the number of banks written
+pub fn disassemble_rom(
+    rom: &[u8],
+    symbols: &SymbolTable,
+    cdl: Option<&CodeDataLog>,
+    out_dir: &str,
+) -> Result<usize, String> {
+    let disassembler = disassemble(rom, symbols, cdl);
+
+    // Write out every bank
+    fs::create_dir_all(out_dir)
+        .map_err(|e| format!("Failed to create: {}. Error: {}", out_dir, e))?;
+    let banks = rom.len().div_ceil(BANK_SIZE);
+    for bank in 0..banks {
+        let file_path = Path::new(out_dir).join(format!("bank_{:03X}.asm", bank));
+        fs::write(&file_path, disassembler.write_bank(bank))
+            .map_err(|e| format!("Failed to write: {}. Error: {}", file_path.display(), e))?;
+    }
+
+    Ok(banks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to build a small bank 0, everything before the entry point is unused 0xFF
+    fn test_rom() -> Vec<u8> {
+        let mut rom = vec![0xFF; 0x100];
+        rom.extend([
+            0x00, // 0100: nop
+            0xC3, 0x08, 0x01, // 0101: jp $0108
+            0x3E, 0x12, // 0104: ld a, $12 (only reached through a bank switch)
+            0xC9, // 0106: ret
+            0x77, // 0107: data
+            0x21, 0x34, 0x12, // 0108: ld hl, $1234
+            0x18, 0xFD, // 010B: jr $010A (into the ld hl operand)
+        ]);
+        rom
+    }
+
+    // Function to get the expected db lines for the unused space
+    fn unused_space() -> String {
+        "    db $FF, $FF, $FF, $FF, $FF, $FF, $FF, $FF\n".repeat(0x100 / 8)
+    }
+
+    #[test]
+    fn disassembles_a_bank_with_a_cdl() {
+        let rom = test_rom();
+        let cdl = CodeDataLog::new(rom.len());
+        cdl.mark_instruction(0x0104, 2);
+        cdl.mark_instruction(0x0106, 1);
+        cdl.mark_data(0x0107);
+        let symbols = SymbolTable::new();
+
+        let output = disassemble(&rom, &symbols, Some(&cdl)).write_bank(0);
+        let expected = format!(
+            "SECTION \"ROM Bank $000\", ROM0[$0000]\n{}\nL_000_0100:\n    nop\n    jp L_000_0108\n    ld a, $12\n    ret\n    db $77\n\nL_000_0108:\n    ld hl, $1234\nDEF L_000_010A EQU @-1\n    jr L_000_010A\n",
+            unused_space()
+        );
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn unreached_bytes_are_data_without_a_cdl() {
+        let rom = test_rom();
+        let symbols = SymbolTable::new();
+
+        let output = disassemble(&rom, &symbols, None).write_bank(0);
+        assert!(output.contains("    jp L_000_0108\n    db $3E, $12, $C9, $77\n\nL_000_0108:\n"));
+    }
+
+    #[test]
+    fn logged_data_is_never_decoded() {
+        // Jump straight at bytes the game only read as data
+        let mut rom = test_rom();
+        rom[0x0102] = 0x04;
+        let cdl = CodeDataLog::new(rom.len());
+        (0x0104..0x0108).for_each(|offset| cdl.mark_data(offset));
+        let symbols = SymbolTable::new();
+
+        let output = disassemble(&rom, &symbols, Some(&cdl)).write_bank(0);
+        assert!(output.contains("\nL_000_0104:\n    db $3E, $12, $C9, $77, $21,"));
+        assert!(!output.contains("ld a, $12"));
+    }
+}
@@ -0,0 +1,968 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::net::TcpStream;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{error, info, warn};
+
+// Import your required modules
+use crate::hdw::bus::Bus;
+use crate::hdw::cart::Cartridge;
+use crate::hdw::cdl::CodeDataLog;
+use crate::hdw::cpu::CPU;
+use crate::hdw::crash::{
+    crash_report, install_panic_hook, take_panic_message, write_crash_report, CRASH_DIR,
+};
+use crate::hdw::debug::{parse_address, Breakpoint, WatchAction, Watchpoint};
+use crate::hdw::disasm::disassemble_rom;
+use crate::hdw::events::{Event, EventLog, EVENT_LOG_LENGTH};
+use crate::hdw::gdb::spawn_gdb_server;
+use crate::hdw::heatmap::Heatmap;
+use crate::hdw::io::io_register_address;
+use crate::hdw::joypad::Button;
+use crate::hdw::logging::{init_logging, DEFAULT_LOG_LEVEL};
+use crate::hdw::model::Model;
+use crate::hdw::remote::{spawn_console, spawn_remote_server};
+use crate::hdw::rom_watch::{RomWatcher, POLL_INTERVAL};
+use crate::hdw::splits::AutoSplitter;
+use crate::hdw::state::{
+    read_header, write_file_atomic, write_header, Savestate, StateReader, StateWriter,
+};
+use crate::hdw::symbols::SymbolTable;
+use crate::hdw::verify::verify_dir;
+
+// T-cycles in one frame (154 lines * 456 dots) and its real time length at 4.194304 MHz
+pub const CYCLES_PER_FRAME: u64 = 70224;
+pub const FRAME_DURATION: Duration = Duration::from_nanos(16_742_706);
+
+// Frames between --heatmap/--cdl/--events file updates (about once a second)
+const DEBUG_OUTPUT_INTERVAL: u64 = 60;
+
+// Emulator context
+pub struct EmuContext {
+    running: bool,
+    paused: bool,
+    pub ticks: u64,
+    pub frames: u64,
+    cpu: CPU, // Add CPU instance to context
+    model: Model,
+    breakpoints: Vec<Breakpoint>,
+    error: Option<String>,
+    serial_out: Option<Box<dyn Write + Send>>,
+    auto_splitter: Option<(AutoSplitter, TcpStream)>,
+    heatmap_out: Option<PathBuf>,
+    cdl_out: Option<PathBuf>,
+    events_out: Option<PathBuf>,
+}
+
+// Creating a static emulator context
+impl EmuContext {
+    pub fn new(bus: Bus) -> Self {
+        EmuContext::with_model(bus, Model::default())
+    }
+
+    // Constructor for a machine other than the default, nothing has run yet so no reset is needed
+    pub fn with_model(bus: Bus, model: Model) -> Self {
+        let mut cpu = CPU::new(bus);
+        model.apply(&mut cpu);
+
+        EmuContext {
+            running: true,
+            paused: false,
+            ticks: 0,
+            frames: 0,
+            cpu, // CPU starts where the boot ROM leaves it
+            model,
+            breakpoints: Vec::new(),
+            error: None,
+            serial_out: None,
+            auto_splitter: None,
+            heatmap_out: None,
+            cdl_out: None,
+            events_out: None,
+        }
+    }
+
+    pub fn execute_cpu_step(&mut self) -> bool {
+        if !self.running || self.paused {
+            return true; // Indicate that the step did not execute
+        }
+
+        // Pause on breakpoints whose condition (if any) holds
+        if let Some(breakpoint) = self
+            .breakpoints
+            .iter()
+            .find(|breakpoint| breakpoint.hits(&self.cpu))
+        {
+            let rom_bank = self.cpu.bus.cart.rom_bank();
+            let location = match self.cpu.symbols.label(self.cpu.pc, rom_bank) {
+                Some(label) => format!("{} ({:04X})", label, self.cpu.pc),
+                None => format!("{:04X}", self.cpu.pc),
+            };
+            let condition = match &breakpoint.condition {
+                Some(condition) => format!(" if {}", condition),
+                None => String::new(),
+            };
+            info!(
+                "Break at {}{}\n{}",
+                location,
+                condition,
+                self.cpu.backtrace().trim_end()
+            );
+            self.paused = true;
+            return true;
+        }
+
+        self.run_cpu_step()
+    }
+
+    // Function to execute one instruction without checking breakpoints
+    fn run_cpu_step(&mut self) -> bool {
+        if !self.running {
+            return true;
+        }
+
+        // Execute a CPU step
+        let result = self.cpu.step(self.ticks);
+
+        if let Err(e) = &result {
+            error!("CPU Stopped: {}", e);
+            self.error = Some(e.clone());
+            self.running = false; // Stop the emulator
+        }
+
+        // Pause on breaking watchpoints
+        if let Some(hit) = self.cpu.bus.take_watch_hit() {
+            info!(
+                "Break on {} at PC {:04X}\n{}",
+                hit,
+                self.cpu.pc,
+                self.cpu.backtrace().trim_end()
+            );
+            self.paused = true;
+        }
+
+        self.ticks += 1;
+        result.is_ok()
+    }
+
+    // Function to check if a breakpoint, watchpoint or debugger has paused emulation
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // Function to check if the CPU is still running (false once it stopped on an error)
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    // Function to pause emulation until resume
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    // Function to resume emulation, stepping off a breakpoint at PC so it doesn't hit again
+    pub fn resume(&mut self) {
+        self.paused = false;
+        if self
+            .breakpoints
+            .iter()
+            .any(|breakpoint| breakpoint.hits(&self.cpu))
+        {
+            self.run_cpu_step();
+        }
+    }
+
+    // Function to execute a single instruction while paused (a breakpoint at PC is stepped over)
+    pub fn step_instruction(&mut self) {
+        self.run_cpu_step();
+    }
+
+    // Function to add a breakpoint (duplicates are ignored)
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    // Function to remove the unconditional breakpoint at an address
+    pub fn remove_breakpoint(&mut self, address: u16) {
+        self.breakpoints
+            .retain(|breakpoint| breakpoint.address != address || breakpoint.condition.is_some());
+    }
+
+    // Function to access the CPU (registers, bus and watchpoints) for debugger front ends
+    pub fn cpu(&self) -> &CPU {
+        &self.cpu
+    }
+
+    // Method to mutably access the CPU
+    pub fn cpu_mut(&mut self) -> &mut CPU {
+        &mut self.cpu
+    }
+
+    // Function to run exactly one frame worth of cycles
+    // Frames always end on a multiple of CYCLES_PER_FRAME so any overshoot carries into the next
+    pub fn run_frame(&mut self) {
+        let frame_end = (self.frames + 1) * CYCLES_PER_FRAME;
+
+        while self.running && !self.paused && self.cpu.cycles < frame_end {
+            self.execute_cpu_step();
+        }
+
+        // Only count the frame if we didn't stop part way through
+        if self.cpu.cycles >= frame_end {
+            self.frames += 1;
+        }
+        self.flush_serial();
+        self.update_splits();
+    }
+
+    // Function to check the auto splitter and send LiveSplit anything it fired
+    fn update_splits(&mut self) {
+        if let Some((splitter, stream)) = &mut self.auto_splitter {
+            let bus = &self.cpu.bus;
+            if let Some(command) = splitter.update(|address| bus.peek_byte(address)) {
+                if let Err(e) = write!(stream, "{}\r\n", command) {
+                    warn!("LiveSplit connection lost: {}", e);
+                    self.auto_splitter = None;
+                }
+            }
+        }
+    }
+
+    // Function to pass anything sent over serial this frame on to the --serial output
+    // Without one the bytes wait for take_serial_output
+    fn flush_serial(&mut self) {
+        if let Some(serial_out) = &mut self.serial_out {
+            let output = self.cpu.bus.serial.take_output();
+            if output.is_empty() {
+                return;
+            }
+            if let Err(e) = serial_out
+                .write_all(&output)
+                .and_then(|_| serial_out.flush())
+            {
+                warn!("Serial output stopped: {}", e);
+                self.serial_out = None;
+            }
+        }
+    }
+
+    // Function to run a frame, turning a panic in the core into a crash report and an error
+    pub fn run_frame_guarded(&mut self) {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.run_frame()));
+        if let Err(payload) = result {
+            let message = take_panic_message(payload.as_ref());
+            let report = crash_report(&self.cpu, &message);
+            match write_crash_report(Path::new(CRASH_DIR), self.cpu.bus.cart.file_name(), &report) {
+                Ok(path) => error!("Crash report written to {}", path.display()),
+                Err(e) => error!("{}\n{}", e, report),
+            }
+            self.error = Some(format!("Emulation crashed: {}", message));
+            self.running = false;
+        }
+    }
+
+    // Function to serialize the whole machine
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        write_header(&mut writer, self.cpu.bus.cart.global_checksum());
+        writer.section(b"EMU ");
+        writer.write_u64(self.ticks);
+        writer.write_u64(self.frames);
+        self.cpu.save_state(&mut writer);
+        writer.finish()
+    }
+
+    // Function to restore the machine from save_state output
+    // A bad state leaves the machine as it was
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), String> {
+        let backup = self.save_state();
+        let result = self.load_sections(data);
+        if result.is_err() {
+            self.load_sections(&backup)?;
+        }
+        result
+    }
+
+    fn load_sections(&mut self, data: &[u8]) -> Result<(), String> {
+        let mut reader = StateReader::new(data);
+        read_header(&mut reader, self.cpu.bus.cart.global_checksum())?;
+        reader.section(b"EMU ")?;
+        self.ticks = reader.read_u64()?;
+        self.frames = reader.read_u64()?;
+        self.cpu.load_state(&mut reader)?;
+        reader.finish()
+    }
+
+    // Function to power cycle the machine, keeping the cart (and its RAM) and debugger setup
+    pub fn reset(&mut self) {
+        let mut cart = std::mem::take(&mut self.cpu.bus.cart);
+        cart.reset();
+        let mut bus = Bus::new(cart);
+        bus.watchpoints = std::mem::take(&mut self.cpu.bus.watchpoints);
+        bus.heatmap = self.cpu.bus.heatmap.take();
+        bus.cdl = self.cpu.bus.cdl.take();
+        bus.events = self.cpu.bus.events.take();
+        if let Some(events) = &mut bus.events {
+            events.push(Event::Reset);
+        }
+
+        let mut cpu = CPU::new(bus);
+        self.model.apply(&mut cpu);
+        cpu.symbols = std::mem::take(&mut self.cpu.symbols);
+        cpu.trace = self.cpu.trace;
+        self.cpu = cpu;
+        self.ticks = 0;
+        self.frames = 0;
+        self.error = None;
+        self.running = true;
+    }
+
+    // Function to re-read the ROM (and its .sym) from disk and power cycle
+    // Breakpoints and watchpoints are kept, keep_ram carries cart RAM over when the size matches
+    pub fn reload_rom(&mut self, keep_ram: bool) -> Result<(), String> {
+        let rom_path = self.cpu.bus.cart.file_name().to_string();
+        let mut cart = Cartridge::new();
+        cart.load_cart(&rom_path)?;
+        if keep_ram && !cart.copy_ram_from(&self.cpu.bus.cart) {
+            warn!("Cart RAM size changed, starting with empty RAM");
+        }
+        // A rebuilt ROM that changed size can't share the old code/data log
+        if let Some(cdl) = &self.cpu.bus.cdl {
+            if cdl.len() != cart.rom_data().len() {
+                warn!("ROM size changed, starting a new code/data log");
+                self.cpu.bus.cdl = Some(CodeDataLog::new(cart.rom_data().len()));
+            }
+        }
+        self.cpu.bus.cart = cart;
+
+        // Labels move when the game is rebuilt
+        let sym_path = Path::new(&rom_path).with_extension("sym");
+        if sym_path.exists() {
+            match SymbolTable::load(&sym_path.to_string_lossy()) {
+                Ok(symbols) => self.cpu.symbols = symbols,
+                Err(e) => warn!("Failed to load symbols: {}", e),
+            }
+        }
+
+        self.reset();
+        info!("Reloaded: {}", rom_path);
+        Ok(())
+    }
+
+    // Function to switch the machine model, power cycles so the boot state takes effect
+    pub fn set_model(&mut self, model: Model) {
+        self.model = model;
+        self.reset();
+    }
+
+    // Function to read memory without side effects (wraps at 0xFFFF)
+    pub fn read_memory(&self, address: u16, length: usize) -> Vec<u8> {
+        (0..length)
+            .map(|i| self.cpu.bus.peek_byte(address.wrapping_add(i as u16)))
+            .collect()
+    }
+
+    // Function to write memory as the CPU would (ROM addresses go to the MBC)
+    pub fn write_memory(&mut self, address: u16, data: &[u8]) {
+        for (i, &value) in data.iter().enumerate() {
+            self.cpu
+                .bus
+                .write_byte(address.wrapping_add(i as u16), value);
+        }
+    }
+
+    // Function to find an address by label, IO register name or hex
+    pub fn parse_address(&self, text: &str) -> Result<u16, String> {
+        parse_address(text, &self.cpu.symbols)
+    }
+
+    // Function to write the --heatmap image, --cdl log and --events timeline if they were asked for
+    fn export_debug_outputs(&self) {
+        if let (Some(path), Some(heatmap)) = (&self.heatmap_out, &self.cpu.bus.heatmap) {
+            if let Err(e) = write_file_atomic(path, &heatmap.render_ppm()) {
+                warn!("Failed to write heatmap: {}", e);
+            }
+        }
+        if let (Some(path), Some(cdl)) = (&self.cdl_out, &self.cpu.bus.cdl) {
+            if let Err(e) = write_file_atomic(path, &cdl.to_bytes()) {
+                warn!("Failed to write code/data log: {}", e);
+            }
+        }
+        if let (Some(path), Some(events)) = (&self.events_out, &self.cpu.bus.events) {
+            let timeline = events.timeline(EVENT_LOG_LENGTH);
+            if let Err(e) = write_file_atomic(path, timeline.as_bytes()) {
+                warn!("Failed to write event log: {}", e);
+            }
+        }
+    }
+
+    // Function to take the error that stopped the CPU, if any
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+
+    // Function to take the bytes sent over serial that haven't been passed on yet
+    pub fn take_serial_output(&mut self) -> Vec<u8> {
+        self.cpu.bus.serial.take_output()
+    }
+
+    // Function to press or release a button
+    pub fn set_button(&mut self, button: Button, pressed: bool) {
+        self.cpu.bus.set_button(button, pressed);
+    }
+
+    // Function to read an IO register by name (LCDC, STAT, NR52...)
+    pub fn io_register(&self, name: &str) -> Option<u8> {
+        let address = io_register_address(name)?;
+        Some(self.cpu.bus.peek_byte(address))
+    }
+
+    // Function to write an IO register by name
+    pub fn set_io_register(&mut self, name: &str, value: u8) -> Result<(), String> {
+        let address = io_register_address(name).ok_or(format!("Unknown IO Register: {}", name))?;
+        self.cpu.bus.write_byte(address, value);
+        Ok(())
+    }
+}
+
+// CPU thread function
+fn cpu_run(ctx: Arc<Mutex<EmuContext>>, mut rom_watcher: Option<RomWatcher>) {
+    let mut next_frame = Instant::now();
+    loop {
+        {
+            let mut ctx_lock = ctx.lock().unwrap();
+
+            if !ctx_lock.running {
+                break;
+            }
+
+            // Rebuilt ROM on disk -> reload it (a bad build keeps the old one running)
+            if rom_watcher
+                .as_mut()
+                .is_some_and(|watcher| watcher.changed())
+            {
+                if let Err(e) = ctx_lock.reload_rom(true) {
+                    warn!("Auto reload failed: {}", e);
+                }
+            }
+
+            // Emulate a whole frame then let go of the lock
+            if !ctx_lock.paused {
+                ctx_lock.run_frame_guarded();
+                if ctx_lock.frames.is_multiple_of(DEBUG_OUTPUT_INTERVAL) {
+                    ctx_lock.export_debug_outputs();
+                }
+            }
+        }
+
+        // Pace to the real frame rate, emulation itself never sleeps
+        next_frame += FRAME_DURATION;
+        let now = Instant::now();
+        if next_frame > now {
+            thread::sleep(next_frame - now);
+        } else {
+            // Running behind -> don't try to catch up
+            next_frame = now;
+        }
+    }
+}
+
+// Function to run a number of frames unpaced and report throughput
+fn bench_run(ctx: &mut EmuContext, frames: u64) -> io::Result<()> {
+    let start = Instant::now();
+    while ctx.running && !ctx.paused && ctx.frames < frames {
+        ctx.run_frame_guarded();
+    }
+    ctx.export_debug_outputs();
+
+    let elapsed = start.elapsed().as_secs_f64();
+    let fps = ctx.frames as f64 / elapsed;
+    println!("Bench: {} frames in {:.3}s", ctx.frames, elapsed);
+    println!(
+        "  Speed            : {:.1} fps ({:.2}x real time)",
+        fps,
+        fps * FRAME_DURATION.as_secs_f64()
+    );
+    println!(
+        "  Instructions     : {} ({:.0}/s)",
+        ctx.ticks,
+        ctx.ticks as f64 / elapsed
+    );
+    if let Some(cdl) = &ctx.cpu.bus.cdl {
+        let (code, data) = cdl.coverage();
+        println!(
+            "  Code/Data Log    : {} code, {} data of {} ROM bytes",
+            code,
+            data,
+            cdl.len()
+        );
+    }
+
+    match ctx.error.take() {
+        Some(e) => Err(io::Error::other(e)),
+        None => Ok(()),
+    }
+}
+
+// Main Emulator Startup Function
+pub fn emu_run(args: Vec<String>) -> io::Result<()> {
+    // Check Submitted Arugemnts
+    if args.len() < 2 {
+        println!("Usage: emu <rom_file> [--watch ADDR[-END][:r|w|rw][:log|break]]... [--break ADDR|LABEL [if CONDITION]]... [--trace] [--disasm OUT_DIR] [--info] [--bench FRAMES] [--serial FILE|-] [--heatmap FILE.ppm] [--cdl FILE] [--events FILE] [--auto-reload] [--model dmg0|dmg|mgb|sgb|sgb2|cgb] [--remote PORT] [--gdb PORT] [--livesplit HOST:PORT SPLITS_FILE] [--log-level LEVEL[,SUBSYSTEM=LEVEL]...]");
+        println!("       emu verify <rom_dir>");
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Missing ROM file argument",
+        ));
+    }
+
+    // Crashes in the core get a report instead of just a backtrace
+    install_panic_hook();
+
+    // Logging has to be up before the cart loads so find the level first
+    let log_spec = args
+        .iter()
+        .position(|arg| arg == "--log-level")
+        .map(|i| {
+            args.get(i + 1).ok_or(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--log-level needs a level",
+            ))
+        })
+        .transpose()?
+        .map_or(DEFAULT_LOG_LEVEL, |spec| spec.as_str());
+    init_logging(log_spec).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    // Check a whole directory of ROMs instead of running one
+    if args[1] == "verify" {
+        let dir = args.get(2).ok_or(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "verify needs a ROM directory",
+        ))?;
+        verify_dir(dir).map_err(io::Error::other)?;
+        return Ok(());
+    }
+
+    // Only show the header
+    let rom_path = &args[1];
+    let mut cart = Cartridge::new();
+    if args[2..].iter().any(|arg| arg == "--info") {
+        cart.load_header(rom_path).map_err(io::Error::other)?;
+        cart.print_info();
+        return Ok(());
+    }
+
+    // Attempt to create Cartridge
+    if let Err(e) = cart.load_cart(rom_path) {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("Failed to load ROM file: {}", e), // Convert the error into a string
+        ));
+    }
+    info!("Cart loaded..");
+
+    // Load symbols from next to the ROM if there are any
+    let sym_path = Path::new(rom_path).with_extension("sym");
+    let mut symbols = SymbolTable::new();
+    if sym_path.exists() {
+        match SymbolTable::load(&sym_path.to_string_lossy()) {
+            Ok(loaded) => {
+                info!("Symbols loaded: {}", loaded.count());
+                symbols = loaded;
+            }
+            Err(e) => warn!("Failed to load symbols: {}", e),
+        }
+    }
+
+    // Initialize Bus and CTX
+    let mut bus = Bus::new(cart);
+    let mut breakpoints = Vec::new();
+    let mut trace = false;
+    let mut auto_reload = false;
+    let mut bench_frames = None;
+    let mut serial_out: Option<Box<dyn Write + Send>> = None;
+    let mut remote_port = None;
+    let mut gdb_port = None;
+    let mut model = Model::default();
+    let mut auto_splitter = None;
+    let mut heatmap_out = None;
+    let mut cdl_out = None;
+    let mut disasm_out = None;
+    let mut events_out = None;
+
+    // Parse Debug Options
+    let mut options = args[2..].iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--watch" => {
+                let spec = options.next().ok_or(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--watch needs a range",
+                ))?;
+                let watchpoint = Watchpoint::parse(spec, &symbols)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                bus.watchpoints.push(watchpoint);
+            }
+            "--break" => {
+                let mut spec = options
+                    .next()
+                    .ok_or(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "--break needs an address",
+                    ))?
+                    .clone();
+                // Unquoted form -> --break ADDR if CONDITION
+                if options.as_slice().first().is_some_and(|next| next == "if") {
+                    options.next();
+                    let condition = options.next().ok_or(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "--break ADDR if needs a condition",
+                    ))?;
+                    spec = format!("{} if {}", spec, condition);
+                }
+                let breakpoint = Breakpoint::parse(&spec, &symbols)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                breakpoints.push(breakpoint);
+            }
+            "--trace" => trace = true,
+            "--auto-reload" => auto_reload = true,
+            "--model" => {
+                let name = options.next().ok_or(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--model needs a model name",
+                ))?;
+                model = Model::parse(name)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            }
+            "--remote" => {
+                let port = options.next().ok_or(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--remote needs a port",
+                ))?;
+                let port = port.parse::<u16>().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidInput, format!("Bad Port: {}", port))
+                })?;
+                remote_port = Some(port);
+            }
+            "--gdb" => {
+                let port = options.next().ok_or(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--gdb needs a port",
+                ))?;
+                let port = port.parse::<u16>().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidInput, format!("Bad Port: {}", port))
+                })?;
+                gdb_port = Some(port);
+            }
+            "--livesplit" => {
+                let (Some(server), Some(splits_path)) = (options.next(), options.next()) else {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "--livesplit needs a server and a splits file",
+                    ));
+                };
+                let splitter = AutoSplitter::load(splits_path, &symbols)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                let stream = TcpStream::connect(server).map_err(|e| {
+                    io::Error::other(format!("Failed to connect: {}. Error: {}", server, e))
+                })?;
+                info!("LiveSplit connected: {}", server);
+                auto_splitter = Some((splitter, stream));
+            }
+            "--serial" => {
+                let path = options.next().ok_or(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--serial needs a file (or - for stdout)",
+                ))?;
+                serial_out = Some(if path == "-" {
+                    Box::new(io::stdout())
+                } else {
+                    Box::new(File::create(path).map_err(|e| {
+                        io::Error::other(format!("Failed to create: {}. Error: {}", path, e))
+                    })?)
+                });
+            }
+            "--heatmap" => {
+                let path = options.next().ok_or(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--heatmap needs an image file",
+                ))?;
+                bus.heatmap = Some(Heatmap::new());
+                heatmap_out = Some(PathBuf::from(path));
+            }
+            "--cdl" => {
+                let path = options.next().ok_or(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--cdl needs a log file",
+                ))?;
+                let cdl = CodeDataLog::load(Path::new(path), bus.cart.rom_data().len())
+                    .map_err(io::Error::other)?;
+                bus.cdl = Some(cdl);
+                cdl_out = Some(PathBuf::from(path));
+            }
+            "--events" => {
+                let path = options.next().ok_or(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--events needs a timeline file",
+                ))?;
+                bus.events = Some(EventLog::new());
+                events_out = Some(PathBuf::from(path));
+            }
+            // Already applied before loading the cart
+            "--log-level" => {
+                options.next();
+            }
+            "--bench" => {
+                let frames = options.next().ok_or(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--bench needs a frame count",
+                ))?;
+                let frames = frames.parse::<u64>().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("Bad Frame Count: {}", frames),
+                    )
+                })?;
+                bench_frames = Some(frames);
+            }
+            "--disasm" => {
+                let out_dir = options.next().ok_or(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--disasm needs an output directory",
+                ))?;
+
+                disasm_out = Some(out_dir);
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Unknown Option: {}", option),
+                ))
+            }
+        }
+    }
+
+    // Disassemble and exit without running, after any --cdl log has been loaded
+    if let Some(out_dir) = disasm_out {
+        let banks = disassemble_rom(bus.cart.rom_data(), &symbols, bus.cdl.as_ref(), out_dir)
+            .map_err(io::Error::other)?;
+        println!("Disassembled {} banks to {}", banks, out_dir);
+        return Ok(());
+    }
+
+    let mut emu_ctx = EmuContext::with_model(bus, model);
+    emu_ctx.cpu.symbols = symbols;
+    emu_ctx.breakpoints = breakpoints;
+    emu_ctx.cpu.trace = trace;
+    emu_ctx.serial_out = serial_out;
+    emu_ctx.auto_splitter = auto_splitter;
+    emu_ctx.heatmap_out = heatmap_out;
+    emu_ctx.cdl_out = cdl_out;
+    emu_ctx.events_out = events_out;
+
+    // Run headless as fast as possible instead of at real speed
+    if let Some(frames) = bench_frames {
+        return bench_run(&mut emu_ctx, frames);
+    }
+
+    let ctx = Arc::new(Mutex::new(emu_ctx));
+
+    // Let outside tools read/write memory
+    if let Some(port) = remote_port {
+        spawn_remote_server(Arc::clone(&ctx), port)?;
+    }
+
+    // Let breaks be resumed from the terminal (gdb drives its own)
+    let breaks = {
+        let ctx_lock = ctx.lock().unwrap();
+        !ctx_lock.breakpoints.is_empty()
+            || ctx_lock
+                .cpu
+                .bus
+                .watchpoints
+                .iter()
+                .any(|watchpoint| watchpoint.action == WatchAction::Break)
+    };
+    if breaks && gdb_port.is_none() {
+        spawn_console(Arc::clone(&ctx));
+    }
+
+    // Hold at the entry point until a debugger attaches and continues
+    if let Some(port) = gdb_port {
+        ctx.lock().unwrap().pause();
+        spawn_gdb_server(Arc::clone(&ctx), port)?;
+    }
+
+    // Spawn a new thread for CPU execution
+    let cpu_ctx = Arc::clone(&ctx);
+    let rom_watcher = auto_reload.then(|| RomWatcher::new(rom_path, POLL_INTERVAL));
+    thread::spawn(move || {
+        cpu_run(cpu_ctx, rom_watcher);
+    });
+
+    // Main loop for UI
+    loop {
+        match ctx.lock() {
+            Ok(ctx_lock) if ctx_lock.running => {}
+            // Hand back whatever stopped the CPU
+            Ok(mut ctx_lock) => match ctx_lock.error.take() {
+                Some(e) => return Err(io::Error::other(e)),
+                None => break,
+            },
+            // CPU thread panicked outside the core while holding the lock
+            Err(_) => return Err(io::Error::other("Emulation thread crashed")),
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    Ok(())
+}
+
+// Function to advance the rest of the hardware by some M-cycles
+pub fn emu_cycles(cpu: &mut CPU, cpu_cycles: u8) {
+    cpu.cycles += cpu_cycles as u64 * 4;
+    cpu.bus.tick(cpu_cycles);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to build a context running a WRAM loop that counts in C100
+    fn counting_context(step: u8) -> EmuContext {
+        let mut ctx = EmuContext::new(Bus::new(Cartridge::new()));
+        // LD A,(C100) ; ADD A,step ; LD (C100),A ; JR -10
+        ctx.write_memory(
+            0xC000,
+            &[0xFA, 0x00, 0xC1, 0xC6, step, 0xEA, 0x00, 0xC1, 0x18, 0xF6],
+        );
+        ctx.cpu.pc = 0xC000;
+        ctx
+    }
+
+    #[test]
+    fn instances_run_independently_on_threads() {
+        let instances: Vec<_> = [1, 3]
+            .into_iter()
+            .map(|step| {
+                thread::spawn(move || {
+                    let mut ctx = counting_context(step);
+                    for _ in 0..3 {
+                        ctx.run_frame_guarded();
+                    }
+                    ctx
+                })
+            })
+            .collect();
+        let contexts: Vec<EmuContext> = instances
+            .into_iter()
+            .map(|instance| instance.join().unwrap())
+            .collect();
+
+        // Same timing on both, only the step differs
+        for ctx in &contexts {
+            assert_eq!(ctx.frames, 3);
+            assert!(ctx.error.is_none());
+        }
+        assert_eq!(contexts[0].ticks, contexts[1].ticks);
+        let counts: Vec<u8> = contexts
+            .iter()
+            .map(|ctx| ctx.read_memory(0xC100, 1)[0])
+            .collect();
+        assert_ne!(counts[0], 0);
+        assert_eq!(counts[1], counts[0].wrapping_mul(3));
+    }
+
+    #[test]
+    fn io_registers_by_name() {
+        let mut ctx = EmuContext::new(Bus::new(Cartridge::new()));
+        assert_eq!(ctx.set_io_register("scy", 0x12), Ok(()));
+        assert_eq!(ctx.io_register("SCY"), Some(0x12));
+
+        assert_eq!(ctx.io_register("NOPE"), None);
+        assert!(ctx.set_io_register("NOPE", 0x12).is_err());
+
+        // Unused bits read back as 1, NR52's channel bits are read only
+        ctx.set_io_register("STAT", 0x00).unwrap();
+        assert_eq!(ctx.io_register("STAT"), Some(0x80));
+        ctx.set_io_register("NR52", 0x8F).unwrap();
+        assert_eq!(ctx.io_register("NR52"), Some(0xF0));
+    }
+
+    #[test]
+    fn startup_model_logs_no_reset() {
+        let mut bus = Bus::new(Cartridge::new());
+        bus.events = Some(EventLog::new());
+        let mut ctx = EmuContext::with_model(bus, Model::Cgb);
+        assert_eq!(ctx.model, Model::Cgb);
+        assert_eq!(ctx.cpu.registers.a, 0x11);
+
+        let resets = |ctx: &EmuContext| {
+            let events = ctx.cpu.bus.events.as_ref().unwrap();
+            events
+                .events()
+                .filter(|(_, event)| *event == Event::Reset)
+                .count()
+        };
+        assert_eq!(resets(&ctx), 0);
+
+        // Switching models later is a power cycle and shows up in the timeline
+        ctx.set_model(Model::Dmg);
+        assert_eq!(resets(&ctx), 1);
+    }
+
+    // Function to build a ROM that keeps writing a marker byte to C000
+    fn marker_rom(marker: u8) -> Vec<u8> {
+        let mut rom = vec![0; 0x8000];
+        // LD A,marker ; LD (C000),A ; JR -7
+        rom[0x100..0x107].copy_from_slice(&[0x3E, marker, 0xEA, 0x00, 0xC0, 0x18, 0xF9]);
+        rom[0x147] = 0x03; // MBC1+RAM+BATTERY
+        rom[0x149] = 0x02; // 8KB RAM
+        rom[0x14D] = rom[0x134..0x14D].iter().fold(0u8, |checksum, byte| {
+            checksum.wrapping_sub(*byte).wrapping_sub(1)
+        });
+        rom
+    }
+
+    #[test]
+    fn reload_picks_up_the_new_rom() {
+        let dir = std::env::temp_dir().join(format!("gb_reload_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let rom_path = dir.join("game.gb");
+        std::fs::write(&rom_path, marker_rom(0x11)).unwrap();
+
+        let mut cart = Cartridge::new();
+        cart.load_cart(&rom_path.to_string_lossy()).unwrap();
+        let mut ctx = EmuContext::new(Bus::new(cart));
+        ctx.add_breakpoint(Breakpoint {
+            address: 0x1234,
+            condition: None,
+        });
+        ctx.run_frame();
+        assert_eq!(ctx.read_memory(0xC000, 1), [0x11]);
+
+        // Save some cart RAM then rebuild the game
+        ctx.write_memory(0x0000, &[0x0A]);
+        ctx.write_memory(0xA000, &[0x99]);
+        std::fs::write(&rom_path, marker_rom(0x22)).unwrap();
+        ctx.reload_rom(true).unwrap();
+        assert_eq!((ctx.frames, ctx.breakpoints[0].address), (0, 0x1234));
+        ctx.run_frame();
+        assert_eq!(ctx.read_memory(0xC000, 1), [0x22]);
+        ctx.write_memory(0x0000, &[0x0A]);
+        assert_eq!(ctx.read_memory(0xA000, 1), [0x99]);
+
+        // Fresh RAM
+        ctx.reload_rom(false).unwrap();
+        ctx.write_memory(0x0000, &[0x0A]);
+        assert_eq!(ctx.read_memory(0xA000, 1), [0x00]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
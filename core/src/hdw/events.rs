@@ -0,0 +1,146 @@
+/*
+
+    Hardware Event Log (--events FILE)
+
+    Timestamped ring buffer of what the hardware did, for timing sensitive bugs
+        Interrupts requested (IF set) and serviced (jump to the vector)
+        ROM/RAM bank switches and cart RAM enables
+        Resets
+    Written out as a timeline, one event per line stamped with frame and cycle within it
+
+*/
+use std::collections::VecDeque;
+use std::fmt::{self, Write};
+
+use crate::hdw::emu::CYCLES_PER_FRAME;
+
+// Events kept, the oldest fall off past this
+pub const EVENT_LOG_LENGTH: usize = 4096;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Event {
+    InterruptRequested(u8),
+    InterruptServiced(u16),
+    RomBank(u8),
+    RamBank(u8),
+    RamEnabled(bool),
+    Reset,
+}
+
+// Function to name an interrupt by its IF bit
+fn interrupt_name(flag: u8) -> &'static str {
+    match flag {
+        0x01 => "VBLANK",
+        0x02 => "STAT",
+        0x04 => "TIMER",
+        0x08 => "SERIAL",
+        _ => "JOYPAD",
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::InterruptRequested(flag) => {
+                write!(f, "IRQ requested  {}", interrupt_name(*flag))
+            }
+            Event::InterruptServiced(vector) => {
+                let flag = 1 << ((vector.saturating_sub(0x40) / 8) as u8).min(4);
+                write!(
+                    f,
+                    "IRQ serviced   {} -> {:04X}",
+                    interrupt_name(flag),
+                    vector
+                )
+            }
+            Event::RomBank(bank) => write!(f, "ROM bank       {:02X}", bank),
+            Event::RamBank(bank) => write!(f, "RAM bank       {:02X}", bank),
+            Event::RamEnabled(true) => write!(f, "RAM enabled"),
+            Event::RamEnabled(false) => write!(f, "RAM disabled"),
+            Event::Reset => write!(f, "Reset"),
+        }
+    }
+}
+
+pub struct EventLog {
+    events: VecDeque<(u64, Event)>,
+    now: u64, // T-cycles since logging started
+}
+
+impl EventLog {
+    // Constructor
+    pub fn new() -> Self {
+        EventLog {
+            events: VecDeque::with_capacity(EVENT_LOG_LENGTH),
+            now: 0,
+        }
+    }
+
+    // Method to move the clock on by some M-cycles
+    pub fn advance(&mut self, m_cycles: u8) {
+        self.now += m_cycles as u64 * 4;
+    }
+
+    // Method to record an event at the current time
+    pub fn push(&mut self, event: Event) {
+        if self.events.len() == EVENT_LOG_LENGTH {
+            self.events.pop_front();
+        }
+        self.events.push_back((self.now, event));
+    }
+
+    // Method to get the events with their T-cycle timestamps, oldest first
+    pub fn events(&self) -> impl Iterator<Item = &(u64, Event)> {
+        self.events.iter()
+    }
+
+    // Method to format the newest count events as a timeline
+    pub fn timeline(&self, count: usize) -> String {
+        let mut timeline = String::new();
+        let skip = self.events.len().saturating_sub(count);
+        for (time, event) in self.events.iter().skip(skip) {
+            // Writing to a String can't fail
+            let _ = writeln!(
+                timeline,
+                "{:>8}:{:05}  {}",
+                time / CYCLES_PER_FRAME,
+                time % CYCLES_PER_FRAME,
+                event
+            );
+        }
+        timeline
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_a_timestamped_ring_of_events() {
+        let mut log = EventLog::new();
+        log.push(Event::Reset);
+        log.advance(1);
+        log.push(Event::InterruptRequested(0x04));
+        log.advance(255);
+        log.push(Event::InterruptServiced(0x50));
+        log.push(Event::RomBank(3));
+
+        assert_eq!(
+            log.timeline(3),
+            "       0:00004  IRQ requested  TIMER\n       0:01024  IRQ serviced   TIMER -> 0050\n       0:01024  ROM bank       03\n"
+        );
+
+        for bank in 0..EVENT_LOG_LENGTH {
+            log.push(Event::RamBank(bank as u8));
+        }
+        assert_eq!(log.events().count(), EVENT_LOG_LENGTH);
+        assert_eq!(log.events().next().unwrap().1, Event::RamBank(0));
+    }
+}
@@ -0,0 +1,499 @@
+/*
+
+    GDB Remote Stub (--gdb PORT)
+
+    Speaks the GDB remote serial protocol on localhost so gdb/lldb compatible front ends
+    (or VSCode through an SM83 debug extension) can set breakpoints, step and poke memory
+    The emulator starts paused with --gdb and waits for the debugger to continue
+
+    Registers are six 16 bit little endian values: AF BC DE HL SP PC
+    Supported packets: ? g G p P m M c s Z z D k plus the qSupported/qAttached handshake
+    Z0/Z1 break on PC, Z2/Z3/Z4 break on write/read/access like --watch ADDR:rw:break
+    Ctrl-C (0x03) while running pauses, D and k both detach and leave the game running
+
+*/
+use std::io::{self, BufReader, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use log::{debug, info, warn};
+
+use crate::hdw::debug::{Breakpoint, WatchAction, Watchpoint};
+use crate::hdw::emu::EmuContext;
+
+const INTERRUPT: u8 = 0x03;
+const REGISTER_COUNT: usize = 6;
+const STOP_TRAP: &str = "S05";
+const STOP_INTERRUPT: &str = "S02";
+const STOP_POLL: Duration = Duration::from_millis(10);
+
+// What the server does after a packet
+#[derive(PartialEq, Debug)]
+pub enum GdbAction {
+    Reply(String),
+    Continue,
+    Detach,
+    Kill,
+}
+
+// What came in from the debugger
+enum Incoming {
+    Packet(String),
+    Interrupt,
+}
+
+// Function to sum a packet body the way the protocol checksums it
+fn checksum(data: &str) -> u8 {
+    data.bytes().fold(0, |sum, byte| sum.wrapping_add(byte))
+}
+
+// Function to frame a packet body as $data#checksum
+pub fn frame_packet(data: &str) -> String {
+    format!("${}#{:02x}", data, checksum(data))
+}
+
+// Function to parse a hex number from a packet
+fn parse_hex(text: &str) -> Result<usize, String> {
+    usize::from_str_radix(text, 16).map_err(|_| format!("Bad Number: {}", text))
+}
+
+// Function to parse a run of hex encoded bytes
+fn parse_hex_bytes(text: &str) -> Result<Vec<u8>, String> {
+    if !text.len().is_multiple_of(2) {
+        return Err(format!("Bad Hex Data: {}", text));
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            let byte = text
+                .get(i..i + 2)
+                .ok_or(format!("Bad Hex Data: {}", text))?;
+            u8::from_str_radix(byte, 16).map_err(|_| format!("Bad Hex Data: {}", text))
+        })
+        .collect()
+}
+
+// Function to hex encode bytes
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Function to split "ADDR,LEN" into an address and a length
+fn parse_range(text: &str) -> Result<(u16, usize), String> {
+    let (address, length) = text.split_once(',').ok_or(format!("Bad Range: {}", text))?;
+    let address = parse_hex(address)?;
+    let length = parse_hex(length)?;
+    if address > 0xFFFF || length > 0x10000 {
+        return Err(format!("Bad Range: {}", text));
+    }
+    Ok((address as u16, length))
+}
+
+// Function to read a register in gdb order
+fn register(ctx: &EmuContext, index: usize) -> u16 {
+    let cpu = ctx.cpu();
+    match index {
+        0 => cpu.registers.get_af(),
+        1 => cpu.registers.get_bc(),
+        2 => cpu.registers.get_de(),
+        3 => cpu.registers.get_hl(),
+        4 => cpu.sp,
+        _ => cpu.pc,
+    }
+}
+
+// Function to write a register in gdb order
+fn set_register(ctx: &mut EmuContext, index: usize, value: u16) {
+    let cpu = ctx.cpu_mut();
+    match index {
+        0 => cpu.registers.set_af(value),
+        1 => cpu.registers.set_bc(value),
+        2 => cpu.registers.set_de(value),
+        3 => cpu.registers.set_hl(value),
+        4 => cpu.sp = value,
+        _ => cpu.pc = value,
+    }
+}
+
+// Function to parse a register number
+fn parse_register(text: &str) -> Result<usize, String> {
+    match parse_hex(text)? {
+        index if index < REGISTER_COUNT => Ok(index),
+        _ => Err(format!("Bad Register: {}", text)),
+    }
+}
+
+// Function to add or remove a breakpoint/watchpoint from a Z or z packet
+fn set_break(ctx: &mut EmuContext, spec: &str, insert: bool) -> Result<GdbAction, String> {
+    let mut fields = spec.split(',');
+    let (Some(kind), Some(address), Some(length)) = (fields.next(), fields.next(), fields.next())
+    else {
+        return Err(format!("Bad Breakpoint: {}", spec));
+    };
+    let (address, length) = parse_range(&format!("{},{}", address, length))?;
+
+    let (on_read, on_write) = match kind {
+        "0" | "1" => {
+            if insert {
+                ctx.add_breakpoint(Breakpoint {
+                    address,
+                    condition: None,
+                });
+            } else {
+                ctx.remove_breakpoint(address);
+            }
+            return Ok(GdbAction::Reply(String::from("OK")));
+        }
+        "2" => (false, true),
+        "3" => (true, false),
+        "4" => (true, true),
+        _ => return Ok(GdbAction::Reply(String::new())),
+    };
+
+    let watchpoint = Watchpoint {
+        start: address,
+        end: (address as usize + length.max(1) - 1).min(0xFFFF) as u16,
+        on_read,
+        on_write,
+        action: WatchAction::Break,
+    };
+    let watchpoints = &mut ctx.cpu_mut().bus.watchpoints;
+    if insert {
+        watchpoints.push(watchpoint);
+    } else if let Some(index) = watchpoints.iter().position(|other| {
+        (
+            other.start,
+            other.end,
+            other.on_read,
+            other.on_write,
+            other.action,
+        ) == (
+            address,
+            watchpoint.end,
+            on_read,
+            on_write,
+            WatchAction::Break,
+        )
+    }) {
+        watchpoints.remove(index);
+    }
+    Ok(GdbAction::Reply(String::from("OK")))
+}
+
+// Function to run one packet against the emulator
+fn run_packet(ctx: &mut EmuContext, packet: &str) -> Result<GdbAction, String> {
+    let ok = || Ok(GdbAction::Reply(String::from("OK")));
+    let Some(command) = packet.chars().next() else {
+        return Ok(GdbAction::Reply(String::new()));
+    };
+    let args = &packet[command.len_utf8()..];
+
+    match command {
+        '?' => Ok(GdbAction::Reply(String::from(STOP_TRAP))),
+        'g' => {
+            let registers: Vec<u8> = (0..REGISTER_COUNT)
+                .flat_map(|index| register(ctx, index).to_le_bytes())
+                .collect();
+            Ok(GdbAction::Reply(hex_bytes(&registers)))
+        }
+        'G' => {
+            let bytes = parse_hex_bytes(args)?;
+            if bytes.len() != REGISTER_COUNT * 2 {
+                return Err(format!("Bad Register Data: {}", args));
+            }
+            for (index, value) in bytes.chunks(2).enumerate() {
+                set_register(ctx, index, u16::from_le_bytes([value[0], value[1]]));
+            }
+            ok()
+        }
+        'p' => {
+            let index = parse_register(args)?;
+            Ok(GdbAction::Reply(hex_bytes(
+                &register(ctx, index).to_le_bytes(),
+            )))
+        }
+        'P' => {
+            let (index, value) = args
+                .split_once('=')
+                .ok_or(format!("Bad Register Write: {}", args))?;
+            let index = parse_register(index)?;
+            let value = parse_hex_bytes(value)?;
+            let [low, high] = value[..] else {
+                return Err(format!("Bad Register Write: {}", args));
+            };
+            set_register(ctx, index, u16::from_le_bytes([low, high]));
+            ok()
+        }
+        'm' => {
+            let (address, length) = parse_range(args)?;
+            Ok(GdbAction::Reply(hex_bytes(
+                &ctx.read_memory(address, length),
+            )))
+        }
+        'M' => {
+            let (range, data) = args
+                .split_once(':')
+                .ok_or(format!("Bad Memory Write: {}", args))?;
+            let (address, length) = parse_range(range)?;
+            let data = parse_hex_bytes(data)?;
+            if data.len() != length {
+                return Err(format!("Bad Memory Write: {}", args));
+            }
+            ctx.write_memory(address, &data);
+            ok()
+        }
+        'c' | 's' => {
+            // Optional address to resume from
+            if !args.is_empty() {
+                let address = parse_hex(args)?;
+                if address > 0xFFFF {
+                    return Err(format!("Bad Address: {}", args));
+                }
+                ctx.cpu_mut().pc = address as u16;
+            }
+            if command == 's' {
+                ctx.step_instruction();
+                return Ok(GdbAction::Reply(String::from(STOP_TRAP)));
+            }
+            ctx.resume();
+            Ok(GdbAction::Continue)
+        }
+        'Z' => set_break(ctx, args, true),
+        'z' => set_break(ctx, args, false),
+        'H' => ok(),
+        'D' => Ok(GdbAction::Detach),
+        'k' => Ok(GdbAction::Kill),
+        'q' => Ok(GdbAction::Reply(String::from(match packet {
+            _ if packet.starts_with("qSupported") => "PacketSize=1000",
+            "qAttached" => "1",
+            "qC" => "QC1",
+            "qfThreadInfo" => "m1",
+            "qsThreadInfo" => "l",
+            _ => "",
+        }))),
+        // Empty reply -> not supported, gdb falls back to something else
+        _ => Ok(GdbAction::Reply(String::new())),
+    }
+}
+
+// Function to handle one packet, failures become an error reply
+pub fn handle_packet(ctx: &mut EmuContext, packet: &str) -> GdbAction {
+    run_packet(ctx, packet).unwrap_or_else(|e| {
+        debug!("GDB packet {} failed: {}", packet, e);
+        GdbAction::Reply(String::from("E01"))
+    })
+}
+
+// Function to read one byte, None when the debugger hung up
+fn read_byte(reader: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut byte = [0];
+    match reader.read(&mut byte)? {
+        0 => Ok(None),
+        _ => Ok(Some(byte[0])),
+    }
+}
+
+// Function to read the next packet (or Ctrl-C), acking good packets and nacking bad ones
+fn read_packet(reader: &mut impl Read, writer: &mut impl Write) -> io::Result<Option<Incoming>> {
+    while let Some(byte) = read_byte(reader)? {
+        match byte {
+            INTERRUPT => return Ok(Some(Incoming::Interrupt)),
+            b'$' => {}
+            // Acks and anything between packets
+            _ => continue,
+        }
+
+        let mut data = Vec::new();
+        loop {
+            match read_byte(reader)? {
+                Some(b'#') => break,
+                Some(byte) => data.push(byte),
+                None => return Ok(None),
+            }
+        }
+        let (Some(high), Some(low)) = (read_byte(reader)?, read_byte(reader)?) else {
+            return Ok(None);
+        };
+
+        let data = String::from_utf8_lossy(&data).into_owned();
+        let expected = std::str::from_utf8(&[high, low])
+            .ok()
+            .and_then(|sum| u8::from_str_radix(sum, 16).ok());
+        if expected == Some(checksum(&data)) {
+            writer.write_all(b"+")?;
+            return Ok(Some(Incoming::Packet(data)));
+        }
+        writer.write_all(b"-")?;
+    }
+    Ok(None)
+}
+
+// Function to lock the context, failing if the emu thread panicked holding it
+fn lock(ctx: &Mutex<EmuContext>) -> io::Result<MutexGuard<'_, EmuContext>> {
+    ctx.lock()
+        .map_err(|_| io::Error::other("Emulation thread crashed"))
+}
+
+// Function to wait while the game runs for a break or Ctrl-C, None when the debugger hung up
+fn wait_for_stop(
+    ctx: &Mutex<EmuContext>,
+    reader: &mut BufReader<TcpStream>,
+) -> io::Result<Option<&'static str>> {
+    reader.get_ref().set_read_timeout(Some(STOP_POLL))?;
+    let stop = loop {
+        {
+            let ctx_lock = lock(ctx)?;
+            if !ctx_lock.is_running() {
+                break Some("W00");
+            }
+            if ctx_lock.is_paused() {
+                break Some(STOP_TRAP);
+            }
+        }
+
+        match read_byte(reader) {
+            Ok(Some(INTERRUPT)) => {
+                lock(ctx)?.pause();
+                break Some(STOP_INTERRUPT);
+            }
+            Ok(Some(_)) => {}
+            Ok(None) => break None,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => return Err(e),
+        }
+    };
+    reader.get_ref().set_read_timeout(None)?;
+    Ok(stop)
+}
+
+// Function to serve one debugger until it detaches or disconnects
+fn serve_client(ctx: &Mutex<EmuContext>, stream: TcpStream) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    lock(ctx)?.pause();
+
+    while let Some(incoming) = read_packet(&mut reader, &mut writer)? {
+        let action = match incoming {
+            Incoming::Packet(packet) => handle_packet(&mut *lock(ctx)?, &packet),
+            Incoming::Interrupt => {
+                lock(ctx)?.pause();
+                GdbAction::Reply(String::from(STOP_INTERRUPT))
+            }
+        };
+
+        match action {
+            GdbAction::Reply(reply) => writer.write_all(frame_packet(&reply).as_bytes())?,
+            GdbAction::Continue => match wait_for_stop(ctx, &mut reader)? {
+                Some(stop) => writer.write_all(frame_packet(stop).as_bytes())?,
+                None => break,
+            },
+            GdbAction::Detach => {
+                writer.write_all(frame_packet("OK").as_bytes())?;
+                break;
+            }
+            GdbAction::Kill => break,
+        }
+    }
+
+    // Don't leave the game frozen once the debugger is gone
+    lock(ctx)?.resume();
+    Ok(())
+}
+
+// Function to start the stub on localhost, one debugger at a time
+pub fn spawn_gdb_server(ctx: Arc<Mutex<EmuContext>>, port: u16) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    info!("Waiting for GDB on 127.0.0.1:{}", port);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    info!("GDB attached");
+                    match serve_client(&ctx, stream) {
+                        Ok(()) => info!("GDB detached"),
+                        Err(e) => warn!("GDB connection dropped: {}", e),
+                    }
+                }
+                Err(e) => warn!("GDB connection failed: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdw::bus::Bus;
+    use crate::hdw::cart::Cartridge;
+
+    fn reply(ctx: &mut EmuContext, packet: &str) -> String {
+        match handle_packet(ctx, packet) {
+            GdbAction::Reply(reply) => reply,
+            action => panic!("{} gave {:?}", packet, action),
+        }
+    }
+
+    #[test]
+    fn frames_and_reads_packets() {
+        assert_eq!(frame_packet("OK"), "$OK#9a");
+
+        let mut acks = Vec::new();
+        let input = b"+$g#67$m0,1#00\x03";
+        let mut reader = &input[..];
+        let packet = read_packet(&mut reader, &mut acks).unwrap();
+        assert!(matches!(packet, Some(Incoming::Packet(ref data)) if data == "g"));
+        // Bad checksum is nacked and skipped
+        let packet = read_packet(&mut reader, &mut acks).unwrap();
+        assert!(matches!(packet, Some(Incoming::Interrupt)));
+        assert_eq!(acks, b"+-");
+        assert!(read_packet(&mut reader, &mut acks).unwrap().is_none());
+    }
+
+    #[test]
+    fn breaks_steps_and_edits_state() {
+        let mut ctx = EmuContext::new(Bus::new(Cartridge::new()));
+
+        // Four NOPs in WRAM, run from there
+        assert_eq!(reply(&mut ctx, "MC000,4:00000000"), "OK");
+        assert_eq!(reply(&mut ctx, "P5=00c0"), "OK");
+        assert_eq!(reply(&mut ctx, "p5"), "00c0");
+        assert_eq!(reply(&mut ctx, "mc000,2"), "0000");
+
+        assert_eq!(reply(&mut ctx, "Z0,c002,1"), "OK");
+        assert_eq!(handle_packet(&mut ctx, "c"), GdbAction::Continue);
+        ctx.run_frame();
+        assert!(ctx.is_paused());
+        assert_eq!(&reply(&mut ctx, "g")[20..], "02c0");
+
+        // Step off the breakpoint
+        assert_eq!(reply(&mut ctx, "s"), STOP_TRAP);
+        assert_eq!(reply(&mut ctx, "p5"), "03c0");
+        assert_eq!(reply(&mut ctx, "z0,c002,1"), "OK");
+
+        // Write watchpoint
+        assert_eq!(reply(&mut ctx, "Z2,d000,2"), "OK");
+        assert_eq!(ctx.cpu().bus.watchpoints.len(), 1);
+        assert_eq!(reply(&mut ctx, "z2,d000,2"), "OK");
+        assert!(ctx.cpu().bus.watchpoints.is_empty());
+
+        // Whole address space
+        assert_eq!(reply(&mut ctx, "Z2,0,10000"), "OK");
+        assert_eq!(ctx.cpu().bus.watchpoints[0].end, 0xFFFF);
+        assert_eq!(reply(&mut ctx, "z2,0,10000"), "OK");
+        assert!(ctx.cpu().bus.watchpoints.is_empty());
+
+        assert_eq!(reply(&mut ctx, "G0000000000000000feff5001"), "OK");
+        assert_eq!((ctx.cpu().sp, ctx.cpu().pc), (0xFFFE, 0x0150));
+
+        assert_eq!(reply(&mut ctx, "p9"), "E01");
+        assert_eq!(reply(&mut ctx, "s10150"), "E01");
+        assert_eq!(ctx.cpu().pc, 0x0150);
+        assert_eq!(reply(&mut ctx, "\u{e9}"), "");
+        assert_eq!(reply(&mut ctx, "vMustReplyEmpty"), "");
+        assert_eq!(handle_packet(&mut ctx, "D"), GdbAction::Detach);
+    }
+}
@@ -0,0 +1,142 @@
+/*
+
+    Memory Access Heatmap (--heatmap FILE)
+
+    Counts reads, writes and instruction fetches per 16 byte bucket of the address space
+    Rendered as a 64x64 grid (one row per 1KB, 0000 top left) into a binary PPM image
+        Red   : writes    (where a game keeps its state)
+        Green : executes  (hot code paths)
+        Blue  : reads
+    Each channel is log scaled against its own busiest bucket so rare accesses still show up
+
+*/
+use std::cell::Cell;
+
+pub const BUCKET_SIZE: usize = 16;
+pub const BUCKETS: usize = 0x10000 / BUCKET_SIZE;
+const GRID_WIDTH: usize = 64;
+const CELL_PIXELS: usize = 4;
+
+// Kind of access being counted
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HeatAccess {
+    Read,
+    Write,
+    Execute,
+}
+
+// Counters live in Cells so reads through &Bus can be counted
+pub struct Heatmap {
+    reads: Vec<Cell<u32>>,
+    writes: Vec<Cell<u32>>,
+    executes: Vec<Cell<u32>>,
+}
+
+impl Heatmap {
+    // Constructor
+    pub fn new() -> Self {
+        let counters = || (0..BUCKETS).map(|_| Cell::new(0)).collect();
+        Heatmap {
+            reads: counters(),
+            writes: counters(),
+            executes: counters(),
+        }
+    }
+
+    fn counters(&self, access: HeatAccess) -> &[Cell<u32>] {
+        match access {
+            HeatAccess::Read => &self.reads,
+            HeatAccess::Write => &self.writes,
+            HeatAccess::Execute => &self.executes,
+        }
+    }
+
+    // Method to count an access
+    pub fn record(&self, address: u16, access: HeatAccess) {
+        let counter = &self.counters(access)[address as usize / BUCKET_SIZE];
+        counter.set(counter.get().saturating_add(1));
+    }
+
+    // Method to get the count for the bucket holding an address
+    pub fn count(&self, address: u16, access: HeatAccess) -> u32 {
+        self.counters(access)[address as usize / BUCKET_SIZE].get()
+    }
+
+    // Method to zero every counter
+    pub fn clear(&self) {
+        for counter in self.reads.iter().chain(&self.writes).chain(&self.executes) {
+            counter.set(0);
+        }
+    }
+
+    // Method to render the heatmap as a binary PPM (P6) image
+    pub fn render_ppm(&self) -> Vec<u8> {
+        let size = GRID_WIDTH * CELL_PIXELS;
+        let mut image = format!("P6\n{} {}\n255\n", size, size).into_bytes();
+
+        // Log scale each channel against its busiest bucket
+        let scale = |access: HeatAccess| {
+            let counters = self.counters(access);
+            let max = counters.iter().map(Cell::get).max().unwrap_or(0).max(1);
+            let max = (max as f64).ln_1p();
+            counters
+                .iter()
+                .map(|counter| ((counter.get() as f64).ln_1p() / max * 255.0) as u8)
+                .collect::<Vec<u8>>()
+        };
+        let (red, green, blue) = (
+            scale(HeatAccess::Write),
+            scale(HeatAccess::Execute),
+            scale(HeatAccess::Read),
+        );
+
+        for y in 0..size {
+            for x in 0..size {
+                let bucket = (y / CELL_PIXELS) * GRID_WIDTH + x / CELL_PIXELS;
+                image.extend_from_slice(&[red[bucket], green[bucket], blue[bucket]]);
+            }
+        }
+        image
+    }
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_and_renders_buckets() {
+        let heatmap = Heatmap::new();
+        heatmap.record(0xC000, HeatAccess::Write);
+        heatmap.record(0xC00F, HeatAccess::Write);
+        heatmap.record(0x0150, HeatAccess::Execute);
+        assert_eq!(heatmap.count(0xC008, HeatAccess::Write), 2);
+        assert_eq!(heatmap.count(0xC010, HeatAccess::Write), 0);
+        assert_eq!(heatmap.count(0x015F, HeatAccess::Execute), 1);
+
+        let image = heatmap.render_ppm();
+        let header = b"P6\n256 256\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        let pixels = &image[header.len()..];
+        assert_eq!(pixels.len(), 256 * 256 * 3);
+
+        // C000 is bucket 0xC00 -> row 48, column 0
+        let pixel = |bucket: usize| {
+            let (row, column) = (bucket / GRID_WIDTH, bucket % GRID_WIDTH);
+            let offset = (row * CELL_PIXELS * 256 + column * CELL_PIXELS) * 3;
+            &pixels[offset..offset + 3]
+        };
+        assert_eq!(pixel(0xC00), [255, 0, 0]);
+        assert_eq!(pixel(0x015), [0, 255, 0]);
+        assert_eq!(pixel(0x000), [0, 0, 0]);
+
+        heatmap.clear();
+        assert_eq!(heatmap.count(0xC000, HeatAccess::Write), 0);
+    }
+}
@@ -7,9 +7,18 @@ use core::panic;
 
 */
 use super::bus::Bus;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    // Every opcode decoded once up front so stepping is a table lookup instead of a match chain
+    static ref DECODE_TABLE: [Option<Instruction>; 256] =
+        std::array::from_fn(|opcode| Instruction::from_byte_not_prefixed(opcode as u8));
+    static ref CB_DECODE_TABLE: [Option<Instruction>; 256] =
+        std::array::from_fn(|opcode| Instruction::from_prefixed_byte(opcode as u8));
+}
 
 // Target For All Instructions
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum Instruction {
     NOP,
     LD(LoadType),
@@ -59,7 +68,7 @@ pub enum Instruction {
 }
 
 // Target All 8 bit and 16 bit register except f
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum AllRegisters {
     A,
     B,
@@ -76,7 +85,7 @@ pub enum AllRegisters {
 }
 
 // Enum For BIT/RES/SET Instruction Types
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum ByteTarget {
     Zero(HLTarget),
     One(HLTarget),
@@ -88,7 +97,7 @@ pub enum ByteTarget {
     Seven(HLTarget),
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum HLTarget {
     A,
     B,
@@ -101,7 +110,7 @@ pub enum HLTarget {
 }
 
 // 16 Bit Targets For Stack
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum StackTarget {
     AF,
     BC,
@@ -110,7 +119,7 @@ pub enum StackTarget {
 }
 
 // Jump Test
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum JumpTest {
     NotZero,
     Zero,
@@ -121,7 +130,7 @@ pub enum JumpTest {
 }
 
 // Enum For Possible Word Load Targets
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum LoadWordTarget {
     BC,
     DE,
@@ -131,7 +140,7 @@ pub enum LoadWordTarget {
 }
 
 // Enum For Possible Word Load Sources
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum LoadWordSource {
     SP,
     N16,
@@ -140,7 +149,7 @@ pub enum LoadWordSource {
 }
 
 // 16 bit addreses to be loaded
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum LoadN16 {
     BC,
     DE,
@@ -149,7 +158,7 @@ pub enum LoadN16 {
 }
 
 // 16 bit registers to be loaded
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum AddN16Target {
     BC,
     DE,
@@ -158,7 +167,7 @@ pub enum AddN16Target {
 }
 
 // Some instructions require differing operations types with differing expected values ADD,ADC,SUB etc
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum OPType {
     LoadA(HLTarget),
     LoadHL(AddN16Target),
@@ -167,7 +176,7 @@ pub enum OPType {
 }
 
 // RST Targets
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum RestTarget {
     Zero,
     One,
@@ -180,27 +189,27 @@ pub enum RestTarget {
 }
 
 // LD Targets For Edge Cases
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum LoadA8Target {
     A8,
     A,
 }
 
 // LD Targets For Edge Cases
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum LoadA16Target {
     A16,
     A,
 }
 
 // LD Targets For Edge Cases
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum LoadACTarget {
     C,
     A,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum OPTarget {
     B,
     C,
@@ -214,7 +223,7 @@ pub enum OPTarget {
 }
 
 // Enum Describes Load Rule
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum LoadType {
     RegInReg(HLTarget, HLTarget),         // Store one register into another
     Word(LoadWordTarget, LoadWordSource), // Like Byte but 16 bit values
@@ -227,26 +236,22 @@ pub enum LoadType {
 }
 
 impl Instruction {
+    // Function to check for the opcodes removed from the SM83 (they lock up the CPU)
+    pub fn is_illegal(opcode: u8) -> bool {
+        matches!(
+            opcode,
+            0xD3 | 0xDB | 0xDD | 0xE3 | 0xE4 | 0xEB | 0xEC | 0xED | 0xF4 | 0xFC | 0xFD
+        )
+    }
+
     // Function to take opcode from cpu and match it to a corresponding Instruction
     pub fn decode_from_opcode(opcode: u8, cart: &Bus, pc: u16) -> Option<Instruction> {
-        let prefixed = opcode == 0xCB;
-
-        // determine if instruction is a PREFIX
-        let instruction_opcode = if prefixed {
-            cart.read_byte(None, pc + 1)
-        } else {
-            opcode
-        };
-
-        // Use enum to translate opcode and store next pc addr
-        let instruction = if prefixed {
-            Instruction::from_prefixed_byte(instruction_opcode)
+        // determine if instruction is a PREFIX and look it up in the matching table
+        if opcode == 0xCB {
+            CB_DECODE_TABLE[cart.read_byte(pc.wrapping_add(1)) as usize]
         } else {
-            Instruction::from_byte_not_prefixed(instruction_opcode)
-        };
-
-        // Implicit Return
-        instruction
+            DECODE_TABLE[opcode as usize]
+        }
     }
 
     // Match Instruction to Prefixed Instruction Set
@@ -310,7 +315,7 @@ impl Instruction {
             0x03 => Some(Instruction::INC(AllRegisters::BC)),
             0x13 => Some(Instruction::INC(AllRegisters::DE)),
             0x23 => Some(Instruction::INC(AllRegisters::HL)),
-            0x43 => Some(Instruction::INC(AllRegisters::SP)),
+            0x33 => Some(Instruction::INC(AllRegisters::SP)),
             0x04 => Some(Instruction::INC(AllRegisters::B)),
             0x14 => Some(Instruction::INC(AllRegisters::D)),
             0x24 => Some(Instruction::INC(AllRegisters::H)),
@@ -323,7 +328,7 @@ impl Instruction {
             0x0B => Some(Instruction::DEC(AllRegisters::BC)),
             0x1B => Some(Instruction::DEC(AllRegisters::DE)),
             0x2B => Some(Instruction::DEC(AllRegisters::HL)),
-            0x4B => Some(Instruction::DEC(AllRegisters::SP)),
+            0x3B => Some(Instruction::DEC(AllRegisters::SP)),
             0x05 => Some(Instruction::DEC(AllRegisters::B)),
             0x15 => Some(Instruction::DEC(AllRegisters::D)),
             0x25 => Some(Instruction::DEC(AllRegisters::H)),
@@ -460,10 +465,9 @@ impl Instruction {
             0xF3 => Some(Instruction::DI),
             // EI
             0xFB => Some(Instruction::EI),
-            0xD3 | 0xE3 | 0xE4 | 0xF4 | 0xCB | 0xDB | 0xEB | 0xEC | 0xFC | 0xDD | 0xED | 0xFD => {
-                panic!("NULL INSTRUCTION READ")
-            }
-            _ => panic!("NOT AN INSTRUCTION"),
+            // Illegal opcodes (D3 DB DD E3 E4 EB EC ED F4 FC FD) and the CB prefix
+            // -> let the CPU report it instead of crashing
+            _ => None,
         }
     }
 
@@ -485,18 +489,20 @@ impl Instruction {
 
     // Function for OP Targets
     fn op_target_helper(byte: u8) -> OPTarget {
+        // 0xC6 - 0xFE take an immediate operand instead of a register
+        if byte >= 0xC0 {
+            return OPTarget::D8;
+        }
         match byte % 8 {
-            0 => Some(OPTarget::B),
-            1 => Some(OPTarget::C),
-            2 => Some(OPTarget::D),
-            3 => Some(OPTarget::E),
-            4 => Some(OPTarget::H),
-            5 => Some(OPTarget::L),
-            6 => Some(OPTarget::HL),
-            7 => Some(OPTarget::A),
-            _ => Some(OPTarget::D8),
+            0 => OPTarget::B,
+            1 => OPTarget::C,
+            2 => OPTarget::D,
+            3 => OPTarget::E,
+            4 => OPTarget::H,
+            5 => OPTarget::L,
+            6 => OPTarget::HL,
+            _ => OPTarget::A,
         }
-        .expect("Math doesn't math") // Unwrap and panic if None
     }
 
     // Determine Instruction # and Associated Register
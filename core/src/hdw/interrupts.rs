@@ -1,4 +1,8 @@
+use crate::hdw::bus::Bus;
+use crate::hdw::call_stack::CallKind;
 use crate::hdw::cpu::CPU;
+use crate::hdw::emu::emu_cycles;
+use crate::hdw::events::Event;
 use crate::hdw::stack::*;
 
 #[derive(Copy, Clone)]
@@ -10,24 +14,37 @@ pub enum Interrupts {
     JOYPAD = 16,
 }
 
-pub fn request_interrupt(req_int: Interrupts) {}
+// Function to raise an interrupt in IF
+pub fn request_interrupt(bus: &mut Bus, req_int: Interrupts) {
+    bus.int_flags |= req_int as u8;
+    if let Some(events) = &mut bus.events {
+        events.push(Event::InterruptRequested(req_int as u8));
+    }
+}
 
 pub fn handle_interrupts(cpu: &mut CPU, address: u16) {
     // Push current PC
     stack_push16(cpu, cpu.pc);
+    cpu.enter_frame(CallKind::Interrupt, cpu.pc, address);
+    if let Some(events) = &mut cpu.bus.events {
+        events.push(Event::InterruptServiced(address));
+    }
 
     // Set PC to new address
     cpu.pc = address;
+
+    // Dispatch takes 5 M-cycles -> 2 waits, 2 for the push and 1 to load PC
+    emu_cycles(cpu, 5);
 }
 
 pub fn int_check(cpu: &mut CPU, address: u16, int_type: Interrupts) -> bool {
     // Check if the specified interrupt type is set and enabled
-    if (cpu.int_flags & int_type as u8) != 0 && (cpu.ie_register & int_type as u8) != 0 {
+    if (cpu.bus.int_flags & int_type as u8) != 0 && (cpu.bus.ie_register & int_type as u8) != 0 {
         // Handle the interrupt by pushing the current PC and setting the new address
         handle_interrupts(cpu, address);
 
         // Clear the interrupt flag for this type, un-halt the CPU, and disable master interrupt
-        cpu.int_flags &= !(int_type as u8);
+        cpu.bus.int_flags &= !(int_type as u8);
         cpu.is_halted = false;
         cpu.master_enabled = false;
 
@@ -0,0 +1,240 @@
+/*
+
+    Gameboy IO Registers
+
+    0xFF00 - 0xFF7F : I/O Registers
+    0xFFFF          : Interrupt Enable
+
+    Holds the backing storage for the IO region and a name table so callers
+    can refer to registers as LCDC, STAT, NR52... instead of raw addresses
+
+    Sound registers follow the NR52 power rules (power off clears and locks NR10 - NR51)
+    Wave RAM is always accessible since channel 3 never plays until there is an APU
+
+*/
+use crate::hdw::state::{Savestate, StateReader, StateWriter};
+
+// Name -> Address table for every named IO register
+pub const IO_REGISTERS: [(&str, u16); 55] = [
+    // Joypad / Serial
+    ("P1", 0xFF00),
+    ("SB", 0xFF01),
+    ("SC", 0xFF02),
+    // Timer
+    ("DIV", 0xFF04),
+    ("TIMA", 0xFF05),
+    ("TMA", 0xFF06),
+    ("TAC", 0xFF07),
+    // Interrupt Flags
+    ("IF", 0xFF0F),
+    // Sound
+    ("NR10", 0xFF10),
+    ("NR11", 0xFF11),
+    ("NR12", 0xFF12),
+    ("NR13", 0xFF13),
+    ("NR14", 0xFF14),
+    ("NR21", 0xFF16),
+    ("NR22", 0xFF17),
+    ("NR23", 0xFF18),
+    ("NR24", 0xFF19),
+    ("NR30", 0xFF1A),
+    ("NR31", 0xFF1B),
+    ("NR32", 0xFF1C),
+    ("NR33", 0xFF1D),
+    ("NR34", 0xFF1E),
+    ("NR41", 0xFF20),
+    ("NR42", 0xFF21),
+    ("NR43", 0xFF22),
+    ("NR44", 0xFF23),
+    ("NR50", 0xFF24),
+    ("NR51", 0xFF25),
+    ("NR52", 0xFF26),
+    // LCD
+    ("LCDC", 0xFF40),
+    ("STAT", 0xFF41),
+    ("SCY", 0xFF42),
+    ("SCX", 0xFF43),
+    ("LY", 0xFF44),
+    ("LYC", 0xFF45),
+    ("DMA", 0xFF46),
+    ("BGP", 0xFF47),
+    ("OBP0", 0xFF48),
+    ("OBP1", 0xFF49),
+    ("WY", 0xFF4A),
+    ("WX", 0xFF4B),
+    // Color Only
+    ("KEY1", 0xFF4D),
+    ("VBK", 0xFF4F),
+    ("HDMA1", 0xFF51),
+    ("HDMA2", 0xFF52),
+    ("HDMA3", 0xFF53),
+    ("HDMA4", 0xFF54),
+    ("HDMA5", 0xFF55),
+    ("RP", 0xFF56),
+    ("BCPS", 0xFF68),
+    ("BCPD", 0xFF69),
+    ("OCPS", 0xFF6A),
+    ("OCPD", 0xFF6B),
+    ("SVBK", 0xFF70),
+    // Interrupt Enable
+    ("IE", 0xFFFF),
+];
+
+// Function to lookup the address of a register by name (case insensitive)
+pub fn io_register_address(name: &str) -> Option<u16> {
+    IO_REGISTERS
+        .iter()
+        .find(|(reg_name, _)| reg_name.eq_ignore_ascii_case(name))
+        .map(|&(_, address)| address)
+}
+
+// Function to lookup the name of a register by address
+pub fn io_register_name(address: u16) -> Option<&'static str> {
+    IO_REGISTERS
+        .iter()
+        .find(|&&(_, reg_address)| reg_address == address)
+        .map(|&(name, _)| name)
+}
+
+// Sound registers with special write rules
+const NR10: u16 = 0xFF10;
+const NR11: u16 = 0xFF11;
+const NR21: u16 = 0xFF16;
+const NR31: u16 = 0xFF1B;
+const NR41: u16 = 0xFF20;
+const NR51: u16 = 0xFF25;
+const NR52: u16 = 0xFF26;
+
+pub struct IO {
+    regs: [u8; 0x80],
+}
+
+// Bits that always read back as 1 (unused bits and unmapped registers on DMG)
+#[rustfmt::skip]
+const UNUSED_BITS: [u8; 0x80] = [
+//  x0    x1    x2    x3    x4    x5    x6    x7    x8    x9    xA    xB    xC    xD    xE    xF
+    0xC0, 0x00, 0x7E, 0xFF, 0x00, 0x00, 0x00, 0xF8, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xE0, // FF0x
+    0x80, 0x3F, 0x00, 0xFF, 0xBF, 0xFF, 0x3F, 0x00, 0xFF, 0xBF, 0x7F, 0xFF, 0x9F, 0xFF, 0xBF, 0xFF, // FF1x
+    0xFF, 0x00, 0x00, 0xBF, 0x00, 0x00, 0x70, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // FF2x
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // FF3x
+    0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, // FF4x
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // FF5x
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // FF6x
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // FF7x
+];
+
+impl IO {
+    // Constructor
+    pub fn new() -> Self {
+        let mut io = IO { regs: [0; 0x80] };
+
+        // Sound is powered on at boot
+        io.regs[(NR52 - 0xFF00) as usize] = 0x80;
+        io
+    }
+
+    // Method to read from io
+    pub fn io_read(&self, address: u16) -> u8 {
+        let offset_address = address - 0xFF00;
+
+        self.regs[offset_address as usize] | UNUSED_BITS[offset_address as usize]
+    }
+
+    // Method to write to io
+    pub fn io_write(&mut self, address: u16, value: u8) {
+        let offset_address = address - 0xFF00;
+
+        match address {
+            // NR52 -> only the power bit is writable, the channel status bits are read only
+            NR52 => {
+                self.regs[offset_address as usize] = value & 0x80;
+
+                // Powering off clears every sound register (wave RAM is kept)
+                if value & 0x80 == 0 {
+                    self.regs[0x10..0x26].fill(0);
+                }
+            }
+            // Sound registers ignore writes while powered off,
+            // except the DMG length timers which keep working (NR11/NR21 without duty)
+            NR10..=NR51 if !self.apu_powered() => match address {
+                NR11 | NR21 => self.regs[offset_address as usize] = value & 0x3F,
+                NR31 | NR41 => self.regs[offset_address as usize] = value,
+                _ => {}
+            },
+            _ => self.regs[offset_address as usize] = value,
+        }
+    }
+
+    // Method to check the NR52 power bit
+    pub fn apu_powered(&self) -> bool {
+        self.regs[(NR52 - 0xFF00) as usize] & 0x80 != 0
+    }
+}
+
+impl Default for IO {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Savestate for IO {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.section(b"IO  ");
+        writer.write_bytes(&self.regs);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        reader.section(b"IO  ")?;
+        reader.read_bytes_into(&mut self.regs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sound_registers_read_with_unused_bits() {
+        let mut io = IO::new();
+        io.io_write(0xFF11, 0x00);
+        io.io_write(0xFF1A, 0x00);
+        io.io_write(0xFF26, 0x8F);
+        assert_eq!(io.io_read(0xFF11), 0x3F);
+        assert_eq!(io.io_read(0xFF1A), 0x7F);
+        assert_eq!(io.io_read(0xFF15), 0xFF);
+        // Channel status bits can't be written
+        assert_eq!(io.io_read(0xFF26), 0xF0);
+    }
+
+    #[test]
+    fn power_off_clears_and_locks_sound_registers() {
+        let mut io = IO::new();
+        io.io_write(0xFF12, 0xF3);
+        io.io_write(0xFF24, 0x77);
+        io.io_write(0xFF30, 0xAB);
+
+        io.io_write(0xFF26, 0x00);
+        assert!(!io.apu_powered());
+        assert_eq!(io.io_read(0xFF12), 0x00);
+        assert_eq!(io.io_read(0xFF24), 0x00);
+        assert_eq!(io.io_read(0xFF26), 0x70);
+        // Wave RAM survives
+        assert_eq!(io.io_read(0xFF30), 0xAB);
+
+        // Writes are ignored while off, except DMG length timers
+        io.io_write(0xFF12, 0xF3);
+        io.io_write(0xFF11, 0xFF);
+        io.io_write(0xFF1B, 0x80);
+        io.io_write(0xFF30, 0xCD);
+        assert_eq!(io.io_read(0xFF12), 0x00);
+        assert_eq!(io.io_read(0xFF11), 0x3F); // duty stays 0, length (write only) taken
+        assert_eq!(io.regs[0x11], 0x3F);
+        assert_eq!(io.regs[0x1B], 0x80);
+        assert_eq!(io.io_read(0xFF30), 0xCD);
+
+        // Power back on
+        io.io_write(0xFF26, 0x80);
+        io.io_write(0xFF12, 0xF3);
+        assert_eq!(io.io_read(0xFF12), 0xF3);
+    }
+}
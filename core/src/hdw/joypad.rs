@@ -0,0 +1,159 @@
+/*
+
+    Joypad (P1/JOYP 0xFF00)
+
+    The 8 buttons sit on a 2x4 matrix, the game picks a row by pulling a select line low
+        Bit 5 : Select action buttons    (0 = selected)
+        Bit 4 : Select direction buttons (0 = selected)
+        Bit 3 : Down  / Start   (0 = pressed)
+        Bit 2 : Up    / Select
+        Bit 1 : Left  / B
+        Bit 0 : Right / A
+    Bits 6-7 are unused and read 1, a row that isn't selected reads all 1s
+
+    The joypad interrupt fires whenever one of the input lines goes from high to low,
+    either from a button press on a selected row or from selecting a row with a button held
+
+*/
+use crate::hdw::state::{Savestate, StateReader, StateWriter};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Button {
+    Right,
+    Left,
+    Up,
+    Down,
+    A,
+    B,
+    Select,
+    Start,
+}
+
+pub struct Joypad {
+    select: u8,
+    pressed: u8,
+}
+
+impl Button {
+    // Bit in Joypad::pressed -> directions in the low nibble, actions in the high nibble
+    fn mask(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl Joypad {
+    // Constructor
+    pub fn new() -> Self {
+        Joypad {
+            select: 0x30,
+            pressed: 0,
+        }
+    }
+
+    // Function to get the 4 input lines (0 = low) for the current select lines
+    fn lines(&self) -> u8 {
+        let mut held = 0;
+        if self.select & 0x10 == 0 {
+            held |= self.pressed & 0x0F;
+        }
+        if self.select & 0x20 == 0 {
+            held |= self.pressed >> 4;
+        }
+        !held & 0x0F
+    }
+
+    // Method to read P1
+    pub fn read(&self) -> u8 {
+        0xC0 | self.select | self.lines()
+    }
+
+    // Method to write P1, returns true if a line went low (joypad interrupt)
+    pub fn write(&mut self, value: u8) -> bool {
+        let before = self.lines();
+        self.select = value & 0x30;
+        before & !self.lines() != 0
+    }
+
+    // Method to press or release a button, returns true if a line went low (joypad interrupt)
+    pub fn set_button(&mut self, button: Button, pressed: bool) -> bool {
+        let before = self.lines();
+        if pressed {
+            self.pressed |= button.mask();
+        } else {
+            self.pressed &= !button.mask();
+        }
+        before & !self.lines() != 0
+    }
+
+    // Method to check if a held button is pulling a selected line low (STOP wakes on this)
+    pub fn line_low(&self) -> bool {
+        self.lines() != 0x0F
+    }
+}
+
+impl Default for Joypad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Held buttons are live input so only the select lines are saved
+impl Savestate for Joypad {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.section(b"JOYP");
+        writer.write_u8(self.select);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        reader.section(b"JOYP")?;
+        self.select = reader.read_u8()? & 0x30;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_selected_row() {
+        let mut joypad = Joypad::new();
+        joypad.set_button(Button::A, true);
+        joypad.set_button(Button::Down, true);
+
+        // Nothing selected -> all high
+        assert_eq!(joypad.read(), 0xFF);
+
+        // Directions
+        joypad.write(0x20);
+        assert_eq!(joypad.read(), 0xE7);
+
+        // Actions
+        joypad.write(0x10);
+        assert_eq!(joypad.read(), 0xDE);
+
+        // Both rows are ANDed together
+        joypad.write(0x00);
+        assert_eq!(joypad.read(), 0xC6);
+    }
+
+    #[test]
+    fn interrupts_on_high_to_low() {
+        let mut joypad = Joypad::new();
+
+        // Press on an unselected row -> no line changes
+        assert!(!joypad.set_button(Button::Start, true));
+
+        // Selecting the row pulls the line low
+        assert!(joypad.write(0x10));
+        assert!(!joypad.write(0x10));
+
+        // Release is low to high
+        assert!(!joypad.set_button(Button::Start, false));
+        assert!(joypad.set_button(Button::Select, true));
+
+        // A second button on an already low line still pulls its own line low
+        assert!(joypad.set_button(Button::B, true));
+        assert!(!joypad.set_button(Button::B, true));
+    }
+}
@@ -0,0 +1,135 @@
+/*
+
+    Logging
+
+    Core modules log through the log crate (error!, warn!, info!, debug!, trace!) and the
+    subsystem is the last part of the module path (cpu, cart, bus, emu, timer...)
+
+    --log-level takes a default level and optional per-subsystem overrides
+        --log-level debug
+        --log-level warn,cart=debug,cpu=trace
+
+    Levels: off, error, warn, info (default), debug, trace
+    Output goes to stderr as "[LEVEL subsystem] message" so it doesn't mix with --trace
+
+*/
+use log::{LevelFilter, Log, Metadata, Record};
+
+pub const DEFAULT_LOG_LEVEL: &str = "info";
+
+pub struct LogFilter {
+    default: LevelFilter,
+    subsystems: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    // Function to parse a --log-level spec
+    pub fn parse(spec: &str) -> Result<LogFilter, String> {
+        let mut filter = LogFilter {
+            default: LevelFilter::Info,
+            subsystems: Vec::new(),
+        };
+
+        for part in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            match part.split_once('=') {
+                Some((subsystem, level)) => {
+                    let level = parse_level(level)?;
+                    filter
+                        .subsystems
+                        .push((subsystem.trim().to_string(), level));
+                }
+                None => filter.default = parse_level(part)?,
+            }
+        }
+        Ok(filter)
+    }
+
+    // Function to get the level for a log target (module path or subsystem name)
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        let subsystem = target.rsplit("::").next().unwrap_or(target);
+        self.subsystems
+            .iter()
+            .rev()
+            .find(|(name, _)| name == subsystem)
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    // Function to get the most verbose level anything is allowed to log at
+    fn max_level(&self) -> LevelFilter {
+        self.subsystems
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level
+        .trim()
+        .parse()
+        .map_err(|_| format!("Bad Log Level: {}", level))
+}
+
+struct Logger {
+    filter: LogFilter,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let target = record.target();
+            eprintln!(
+                "[{:<5} {}] {}",
+                record.level(),
+                target.rsplit("::").next().unwrap_or(target),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+// Function to install the logger, only the first call takes effect
+pub fn init_logging(spec: &str) -> Result<(), String> {
+    let filter = LogFilter::parse(spec)?;
+    let max_level = filter.max_level();
+
+    // The logger lives for the rest of the program
+    if log::set_logger(Box::leak(Box::new(Logger { filter }))).is_ok() {
+        log::set_max_level(max_level);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_default_and_subsystem_levels() {
+        let filter = LogFilter::parse("warn, cart=debug,cpu=trace").unwrap();
+        assert_eq!(filter.level_for("gameboy::hdw::cart"), LevelFilter::Debug);
+        assert_eq!(filter.level_for("cpu"), LevelFilter::Trace);
+        assert_eq!(filter.level_for("gameboy::hdw::bus"), LevelFilter::Warn);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+
+        let filter = LogFilter::parse("").unwrap();
+        assert_eq!(filter.level_for("emu"), LevelFilter::Info);
+        assert_eq!(
+            LogFilter::parse("OFF").unwrap().max_level(),
+            LevelFilter::Off
+        );
+
+        assert!(LogFilter::parse("loud").is_err());
+        assert!(LogFilter::parse("cart=").is_err());
+    }
+}
@@ -0,0 +1,38 @@
+/*
+    hdw mod file to allow files to be shared between eachother
+*/
+pub mod bus;
+pub mod call_stack;
+pub mod cart;
+pub mod cdl;
+pub mod cpu;
+pub mod cpu_ops;
+pub mod cpu_util;
+pub mod crash;
+pub mod cycles;
+pub mod debug;
+pub mod disasm;
+pub mod emu;
+pub mod events;
+pub mod gdb;
+pub mod heatmap;
+pub mod instructions;
+pub mod interrupts;
+pub mod io;
+pub mod joypad;
+pub mod logging;
+pub mod model;
+pub mod patch;
+pub mod ram;
+pub mod registers;
+pub mod remote;
+pub mod rom_watch;
+pub mod serial;
+pub mod splits;
+pub mod stack;
+pub mod state;
+pub mod symbols;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+pub mod timer;
+pub mod verify;
@@ -0,0 +1,104 @@
+/*
+
+    Machine Models
+
+    The boot ROM leaves different register values behind on each model and some games check
+    them at 0x0100 (A = 0x11 means CGB, A = 0xFF means MGB/SGB2...) to pick features
+
+    Post boot values (pandocs "Power Up Sequence"):
+        Model  A   F              B   C   D   E   H   L   DIV
+        DMG0   01  00             FF  13  00  C1  84  03  18
+        DMG    01  Z, H+C if hdr  00  13  00  D8  01  4D  AB
+        MGB    FF  Z, H+C if hdr  00  13  00  D8  01  4D  AB
+        SGB    01  00             00  14  00  00  C0  60  --
+        SGB2   FF  00             00  14  00  00  C0  60  --
+        CGB    11  Z              00  00  FF  56  00  0D  --
+
+    H and C are set on DMG/MGB unless the header checksum byte is 0
+    DIV on SGB/CGB depends on boot timing so it's left at 0
+    PC is 0x0100 and SP 0xFFFE on every model
+
+*/
+use crate::hdw::cpu::CPU;
+use crate::hdw::registers::FlagsRegister;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Model {
+    Dmg0,
+    #[default]
+    Dmg,
+    Mgb,
+    Sgb,
+    Sgb2,
+    Cgb,
+}
+
+impl Model {
+    // Function to parse a --model name
+    pub fn parse(name: &str) -> Result<Model, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "dmg0" => Ok(Model::Dmg0),
+            "dmg" => Ok(Model::Dmg),
+            "mgb" => Ok(Model::Mgb),
+            "sgb" => Ok(Model::Sgb),
+            "sgb2" => Ok(Model::Sgb2),
+            "cgb" => Ok(Model::Cgb),
+            _ => Err(format!("Unknown Model: {}", name)),
+        }
+    }
+
+    // Function to put the CPU in the state this model's boot ROM hands over in
+    pub fn apply(self, cpu: &mut CPU) {
+        let header_flags = cpu.bus.cart.header_checksum_byte() != 0;
+        let (a, f, bc, de, hl, div) = match self {
+            Model::Dmg0 => (0x01, 0x00, 0xFF13, 0x00C1, 0x8403, Some(0x18)),
+            Model::Dmg | Model::Mgb => {
+                let a = if self == Model::Dmg { 0x01 } else { 0xFF };
+                let f = if header_flags { 0xB0 } else { 0x80 };
+                (a, f, 0x0013, 0x00D8, 0x014D, Some(0xAB))
+            }
+            Model::Sgb => (0x01, 0x00, 0x0014, 0x0000, 0xC060, None),
+            Model::Sgb2 => (0xFF, 0x00, 0x0014, 0x0000, 0xC060, None),
+            Model::Cgb => (0x11, 0x80, 0x0000, 0xFF56, 0x000D, None),
+        };
+
+        cpu.registers.a = a;
+        cpu.registers.f = FlagsRegister::from(f);
+        cpu.registers.set_bc(bc);
+        cpu.registers.set_de(de);
+        cpu.registers.set_hl(hl);
+        cpu.pc = 0x0100;
+        cpu.sp = 0xFFFE;
+        if let Some(div) = div {
+            cpu.bus.timer.set_div(div);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdw::bus::Bus;
+    use crate::hdw::cart::Cartridge;
+
+    #[test]
+    fn boot_registers_per_model() {
+        let mut cpu = CPU::new(Bus::new(Cartridge::new()));
+
+        Model::Dmg.apply(&mut cpu);
+        assert_eq!(cpu.registers.get_af(), 0x0180);
+        assert_eq!(cpu.registers.get_hl(), 0x014D);
+        assert_eq!((cpu.pc, cpu.sp), (0x0100, 0xFFFE));
+        assert_eq!(cpu.bus.read_byte(0xFF04), 0xAB);
+
+        Model::Mgb.apply(&mut cpu);
+        assert_eq!(cpu.registers.a, 0xFF);
+
+        Model::Cgb.apply(&mut cpu);
+        assert_eq!(cpu.registers.get_af(), 0x1180);
+        assert_eq!(cpu.registers.get_de(), 0xFF56);
+
+        assert_eq!(Model::parse("SGB2"), Ok(Model::Sgb2));
+        assert!(Model::parse("gba").is_err());
+    }
+}
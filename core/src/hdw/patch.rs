@@ -0,0 +1,342 @@
+/*
+
+    ROM Patches (IPS / BPS)
+
+    A patch next to the ROM (game.ips / game.bps) or in a patches/ folder beside it
+    (patches/game.bps) is applied in memory when the cart loads, the ROM file is never changed
+
+    IPS : "PATCH", then records of 3 byte offset + 2 byte size + data until "EOF"
+          A size of 0 is an RLE record (2 byte count + 1 fill byte), an optional
+          3 byte length after "EOF" truncates the ROM
+    BPS : "BPS1", varint sizes and metadata, copy actions, then CRC32s of the source,
+          target and patch which are all checked so a patch for another ROM revision fails
+          The patch CRC only catches corruption, so every size and offset is bounds checked
+          too and the target can't be bigger than the largest ROM a header allows (8 MiB)
+
+*/
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Largest ROM a header can describe (size code 0x08), no patch may build anything bigger
+const MAX_TARGET_SIZE: usize = 8 * 1024 * 1024;
+
+// Function to find a patch for a ROM, BPS first since it's checksummed
+pub fn find_patch(rom_path: &Path) -> Option<PathBuf> {
+    let stem = rom_path.file_stem()?;
+    let patch_dir = rom_path.with_file_name("patches");
+
+    ["bps", "ips"]
+        .iter()
+        .flat_map(|extension| {
+            [
+                rom_path.with_extension(extension),
+                patch_dir.join(stem).with_extension(extension),
+            ]
+        })
+        .find(|path| path.is_file())
+}
+
+// Function to load a patch file and apply it to a ROM
+pub fn apply_patch_file(rom: &[u8], patch_path: &Path) -> Result<Vec<u8>, String> {
+    let patch = fs::read(patch_path)
+        .map_err(|e| format!("Failed to open: {}. Error: {}", patch_path.display(), e))?;
+
+    if patch.starts_with(b"PATCH") {
+        apply_ips(rom, &patch)
+    } else if patch.starts_with(b"BPS1") {
+        apply_bps(rom, &patch)
+    } else {
+        Err(format!("Unknown Patch Format: {}", patch_path.display()))
+    }
+}
+
+// Function to apply an IPS patch
+pub fn apply_ips(rom: &[u8], patch: &[u8]) -> Result<Vec<u8>, String> {
+    let truncated = || String::from("IPS Patch Truncated");
+    let mut target = rom.to_vec();
+    let mut pos = 5;
+
+    loop {
+        let record = patch.get(pos..pos + 3).ok_or_else(truncated)?;
+        pos += 3;
+        if record == b"EOF" {
+            break;
+        }
+        let offset = (record[0] as usize) << 16 | (record[1] as usize) << 8 | record[2] as usize;
+
+        let size = patch.get(pos..pos + 2).ok_or_else(truncated)?;
+        let size = u16::from_be_bytes([size[0], size[1]]) as usize;
+        pos += 2;
+
+        // Size 0 -> run of one byte
+        let (length, data) = if size == 0 {
+            let rle = patch.get(pos..pos + 3).ok_or_else(truncated)?;
+            pos += 3;
+            (u16::from_be_bytes([rle[0], rle[1]]) as usize, None)
+        } else {
+            let data = patch.get(pos..pos + size).ok_or_else(truncated)?;
+            pos += size;
+            (size, Some(data))
+        };
+
+        // Records can grow the ROM
+        if target.len() < offset + length {
+            target.resize(offset + length, 0);
+        }
+        match data {
+            Some(data) => target[offset..offset + length].copy_from_slice(data),
+            None => target[offset..offset + length].fill(patch[pos - 1]),
+        }
+    }
+
+    // Optional truncation
+    if let Some(length) = patch.get(pos..pos + 3) {
+        target
+            .truncate((length[0] as usize) << 16 | (length[1] as usize) << 8 | length[2] as usize);
+    }
+    Ok(target)
+}
+
+// Function to decode a BPS variable length number
+fn read_varint(patch: &[u8], pos: &mut usize) -> Result<usize, String> {
+    let mut value: usize = 0;
+    let mut shift: usize = 1;
+    loop {
+        let byte = *patch.get(*pos).ok_or("BPS Patch Truncated")?;
+        *pos += 1;
+        value = (byte as usize & 0x7F)
+            .checked_mul(shift)
+            .and_then(|add| value.checked_add(add))
+            .ok_or("Bad BPS Number")?;
+        if byte & 0x80 != 0 {
+            return Ok(value);
+        }
+        shift = shift.checked_mul(0x80).ok_or("Bad BPS Number")?;
+        value = value.checked_add(shift).ok_or("Bad BPS Number")?;
+    }
+}
+
+// Function to decode a BPS relative offset (sign in bit 0)
+fn read_offset(patch: &[u8], pos: &mut usize, base: usize) -> Result<usize, String> {
+    let data = read_varint(patch, pos)?;
+    let distance = data >> 1;
+    if data & 1 != 0 {
+        base.checked_sub(distance)
+    } else {
+        base.checked_add(distance)
+    }
+    .ok_or(String::from("Bad BPS Copy Offset"))
+}
+
+// Function to apply a BPS patch
+pub fn apply_bps(rom: &[u8], patch: &[u8]) -> Result<Vec<u8>, String> {
+    if patch.len() < 4 + 12 {
+        return Err(String::from("BPS Patch Truncated"));
+    }
+    let footer = patch.len() - 12;
+    let crc =
+        |at: usize| u32::from_le_bytes([patch[at], patch[at + 1], patch[at + 2], patch[at + 3]]);
+    if crc32(&patch[..patch.len() - 4]) != crc(footer + 8) {
+        return Err(String::from("BPS Patch is corrupt (patch CRC mismatch)"));
+    }
+    if crc32(rom) != crc(footer) {
+        return Err(String::from(
+            "BPS Patch is for a different ROM (source CRC mismatch)",
+        ));
+    }
+
+    let mut pos = 4;
+    let source_size = read_varint(patch, &mut pos)?;
+    let target_size = read_varint(patch, &mut pos)?;
+    if target_size > MAX_TARGET_SIZE {
+        return Err(format!(
+            "BPS Patch target is too large: {} bytes",
+            target_size
+        ));
+    }
+    let metadata_size = read_varint(patch, &mut pos)?;
+    pos = pos
+        .checked_add(metadata_size)
+        .filter(|&pos| pos <= footer)
+        .ok_or("BPS Patch Truncated")?;
+    if source_size != rom.len() {
+        return Err(String::from(
+            "BPS Patch is for a different ROM (size mismatch)",
+        ));
+    }
+
+    let bad_copy = || String::from("Bad BPS Copy");
+    let mut target = Vec::with_capacity(target_size);
+    let (mut source_offset, mut target_offset) = (0, 0);
+    while pos < footer {
+        let data = read_varint(patch, &mut pos)?;
+        let length = (data >> 2) + 1;
+        let out = target.len();
+        // Every action has to fit in the target size the patch declared
+        if length > target_size - out {
+            return Err(bad_copy());
+        }
+        let span = |start: usize| start.checked_add(length).map(|end| start..end);
+
+        match data & 0x03 {
+            // SourceRead -> same position in the source
+            0 => target.extend_from_slice(rom.get(out..out + length).ok_or_else(bad_copy)?),
+            // TargetRead -> bytes stored in the patch
+            1 => {
+                let bytes = span(pos)
+                    .and_then(|range| patch.get(range))
+                    .ok_or_else(bad_copy)?;
+                target.extend_from_slice(bytes);
+                pos += length;
+            }
+            // SourceCopy -> anywhere in the source
+            2 => {
+                source_offset = read_offset(patch, &mut pos, source_offset)?;
+                let bytes = span(source_offset)
+                    .and_then(|range| rom.get(range))
+                    .ok_or_else(bad_copy)?;
+                target.extend_from_slice(bytes);
+                source_offset += length;
+            }
+            // TargetCopy -> earlier output, byte by byte since it can overlap itself
+            _ => {
+                target_offset = read_offset(patch, &mut pos, target_offset)?;
+                if target_offset >= out {
+                    return Err(bad_copy());
+                }
+                for _ in 0..length {
+                    target.push(target[target_offset]);
+                    target_offset += 1;
+                }
+            }
+        }
+    }
+
+    if target.len() != target_size || crc32(&target) != crc(footer + 4) {
+        return Err(String::from("BPS Patch failed (target CRC mismatch)"));
+    }
+    Ok(target)
+}
+
+// Function to get the CRC32 (IEEE) of some data
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(0xFFFF_FFFF, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| {
+            (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_ips_records() {
+        let rom = vec![0u8; 8];
+        let mut patch = b"PATCH".to_vec();
+        // 2 bytes at 1, a run of 3 x 0xAA at 4, then grow to 10 bytes
+        patch.extend_from_slice(&[0, 0, 1, 0, 2, 0x12, 0x34]);
+        patch.extend_from_slice(&[0, 0, 4, 0, 0, 0, 3, 0xAA]);
+        patch.extend_from_slice(&[0, 0, 9, 0, 1, 0x99]);
+        patch.extend_from_slice(b"EOF");
+
+        let patched = apply_ips(&rom, &patch).unwrap();
+        assert_eq!(patched, [0, 0x12, 0x34, 0, 0xAA, 0xAA, 0xAA, 0, 0, 0x99]);
+
+        // Truncation after EOF
+        patch.extend_from_slice(&[0, 0, 3]);
+        assert_eq!(apply_ips(&rom, &patch).unwrap(), [0, 0x12, 0x34]);
+
+        assert!(apply_ips(&rom, b"PATCH\x00\x00").is_err());
+    }
+
+    fn varint(patch: &mut Vec<u8>, mut value: usize) {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                patch.push(byte | 0x80);
+                return;
+            }
+            patch.push(byte);
+            value -= 1;
+        }
+    }
+
+    #[test]
+    fn applies_bps_actions() {
+        let rom: Vec<u8> = (0..200).map(|i| i as u8).collect();
+        let mut target = rom[..4].to_vec();
+        target.extend_from_slice(b"HIHIHIH");
+        target.extend_from_slice(&rom[150..153]);
+
+        let mut patch = b"BPS1".to_vec();
+        varint(&mut patch, rom.len());
+        varint(&mut patch, target.len());
+        varint(&mut patch, 0);
+        varint(&mut patch, 3 << 2); // SourceRead 4
+        varint(&mut patch, (1 << 2) | 1); // TargetRead 2
+        patch.extend_from_slice(b"HI");
+        varint(&mut patch, (4 << 2) | 3); // TargetCopy 5 from +4 (overlapping)
+        varint(&mut patch, 4 << 1);
+        varint(&mut patch, (2 << 2) | 2); // SourceCopy 3 from +150
+        varint(&mut patch, 150 << 1);
+        patch.extend_from_slice(&crc32(&rom).to_le_bytes());
+        patch.extend_from_slice(&crc32(&target).to_le_bytes());
+        patch.extend_from_slice(&crc32(&patch).to_le_bytes());
+
+        assert_eq!(apply_bps(&rom, &patch).unwrap(), target);
+
+        // Wrong source ROM and corrupt patch
+        assert!(apply_bps(&rom[1..], &patch).is_err());
+        let last = patch.len() - 20;
+        patch[last] ^= 1;
+        assert!(apply_bps(&rom, &patch).is_err());
+    }
+
+    // Function to wrap BPS actions with a header and valid source/patch CRCs
+    fn bps_patch(rom: &[u8], target_size: usize, metadata_size: usize, actions: &[u8]) -> Vec<u8> {
+        let mut patch = b"BPS1".to_vec();
+        varint(&mut patch, rom.len());
+        varint(&mut patch, target_size);
+        varint(&mut patch, metadata_size);
+        patch.extend_from_slice(actions);
+        patch.extend_from_slice(&crc32(rom).to_le_bytes());
+        patch.extend_from_slice(&[0; 4]);
+        patch.extend_from_slice(&crc32(&patch).to_le_bytes());
+        patch
+    }
+
+    #[test]
+    fn rejects_crafted_bps_sizes() {
+        let rom = vec![0x11; 16];
+
+        // Target bigger than any ROM, and sizes that overflow
+        assert!(apply_bps(&rom, &bps_patch(&rom, MAX_TARGET_SIZE + 1, 0, &[])).is_err());
+        assert!(apply_bps(&rom, &bps_patch(&rom, usize::MAX, 0, &[])).is_err());
+        assert!(apply_bps(&rom, &bps_patch(&rom, 4, usize::MAX, &[])).is_err());
+
+        // TargetCopy running past the declared target size
+        let mut actions = Vec::new();
+        varint(&mut actions, 0); // SourceRead 1
+        varint(&mut actions, (100 << 2) | 3); // TargetCopy 101 from 0
+        varint(&mut actions, 0);
+        assert!(apply_bps(&rom, &bps_patch(&rom, 4, 0, &actions)).is_err());
+
+        // SourceCopy offset wrapping below 0 or past usize
+        let mut actions = Vec::new();
+        varint(&mut actions, 2); // SourceCopy 1 from -1
+        varint(&mut actions, 3);
+        assert!(apply_bps(&rom, &bps_patch(&rom, 4, 0, &actions)).is_err());
+        let mut actions = Vec::new();
+        varint(&mut actions, 2);
+        varint(&mut actions, usize::MAX - 1);
+        assert!(apply_bps(&rom, &bps_patch(&rom, 4, 0, &actions)).is_err());
+    }
+
+    #[test]
+    fn crc32_matches_reference() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
@@ -1,5 +1,7 @@
 use core::panic;
 
+use crate::hdw::state::{Savestate, StateReader, StateWriter};
+
 pub struct RAM {
     wram: [u8; 0x2000],
     hram: [u8; 0x80],
@@ -46,3 +48,17 @@ impl RAM {
         self.hram[offset_address as usize] = value;
     }
 }
+
+impl Savestate for RAM {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.section(b"RAM ");
+        writer.write_bytes(&self.wram);
+        writer.write_bytes(&self.hram);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        reader.section(b"RAM ")?;
+        reader.read_bytes_into(&mut self.wram)?;
+        reader.read_bytes_into(&mut self.hram)
+    }
+}
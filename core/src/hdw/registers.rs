@@ -1,3 +1,5 @@
+use crate::hdw::state::{Savestate, StateReader, StateWriter};
+
 // FLAG POSITIONS FOR FLAGS REGISTER
 const ZERO_FLAG_BYTE_POSITION: u8 = 7;
 const SUBTRACT_FLAG_BYTE_POSITION: u8 = 6;
@@ -89,3 +91,22 @@ impl std::convert::From<u8> for FlagsRegister {
         }
     }
 }
+
+impl Savestate for Registers {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.section(b"REGS");
+        writer.write_u16(self.get_af());
+        writer.write_u16(self.get_bc());
+        writer.write_u16(self.get_de());
+        writer.write_u16(self.get_hl());
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        reader.section(b"REGS")?;
+        self.set_af(reader.read_u16()?);
+        self.set_bc(reader.read_u16()?);
+        self.set_de(reader.read_u16()?);
+        self.set_hl(reader.read_u16()?);
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+/*
+
+    Remote Memory Access (--remote PORT)
+
+    A plain text TCP server on localhost so trackers, randomizer checkers and other tools can
+    read/write memory while a game runs, one command per line and one reply line each
+
+        READ  <addr> [len]      -> OK 01 02 03...     (len defaults to 1, max 0x10000)
+        WRITE <addr> <bytes...> -> OK                 (hex bytes, e.g. WRITE D35E 01 FF)
+        RESET                   -> OK                 (power cycle, cart RAM is kept)
+        RELOAD [KEEPRAM]        -> OK                 (re-read the ROM from disk and power cycle)
+        FRAME                   -> OK <frame count>
+        PAUSE                   -> OK <pc>
+        CONTINUE (or C)         -> OK                 (resume after a break)
+        STEP (or S)             -> OK <pc>            (run one instruction while paused)
+        BACKTRACE (or BT)       -> OK <frames>        (shadow call stack, innermost first)
+        Anything wrong          -> ERR <message>
+
+    Addresses take hex (C000, 0xC000, $C000), IO register names (LCDC) or symbols from the .sym
+    Commands run between frames while the emu thread isn't holding the context
+    With breakpoints set the same commands are read from stdin, so a break can be resumed
+
+*/
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use log::{info, warn};
+
+use crate::hdw::emu::EmuContext;
+
+// Function to parse a hex byte or length
+fn parse_hex(text: &str) -> Result<usize, String> {
+    let hex = text
+        .trim_start_matches("0x")
+        .trim_start_matches("0X")
+        .trim_start_matches('$');
+    usize::from_str_radix(hex, 16).map_err(|_| format!("Bad Number: {}", text))
+}
+
+// Function to run one command against the emulator, returns the reply without the OK
+pub fn handle_command(ctx: &mut EmuContext, line: &str) -> Result<String, String> {
+    let mut words = line.split_whitespace();
+    let command = words.next().unwrap_or("").to_ascii_uppercase();
+    let args: Vec<&str> = words.collect();
+
+    match (command.as_str(), args.as_slice()) {
+        ("READ", [address, rest @ ..]) if rest.len() <= 1 => {
+            let address = ctx.parse_address(address)?;
+            let length = rest.first().map_or(Ok(1), |length| parse_hex(length))?;
+            if length > 0x10000 {
+                return Err(format!("Read Too Long: {:X}", length));
+            }
+            let bytes = ctx.read_memory(address, length);
+            Ok(bytes
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect::<Vec<_>>()
+                .join(" "))
+        }
+        ("WRITE", [address, bytes @ ..]) if !bytes.is_empty() => {
+            let address = ctx.parse_address(address)?;
+            let bytes = bytes
+                .iter()
+                .map(|byte| match parse_hex(byte)? {
+                    value @ 0..=0xFF => Ok(value as u8),
+                    _ => Err(format!("Bad Byte: {}", byte)),
+                })
+                .collect::<Result<Vec<u8>, String>>()?;
+            ctx.write_memory(address, &bytes);
+            Ok(String::new())
+        }
+        ("RESET", []) => {
+            ctx.reset();
+            Ok(String::new())
+        }
+        ("RELOAD", []) => ctx.reload_rom(false).map(|_| String::new()),
+        ("RELOAD", [keep]) if keep.eq_ignore_ascii_case("KEEPRAM") => {
+            ctx.reload_rom(true).map(|_| String::new())
+        }
+        ("FRAME", []) => Ok(ctx.frames.to_string()),
+        ("PAUSE", []) => {
+            ctx.pause();
+            Ok(format!("{:04X}", ctx.cpu().pc))
+        }
+        ("CONTINUE" | "C", []) => {
+            ctx.resume();
+            Ok(String::new())
+        }
+        ("STEP" | "S", []) => {
+            if !ctx.is_paused() {
+                return Err(String::from("Not Paused"));
+            }
+            ctx.step_instruction();
+            Ok(format!("{:04X}", ctx.cpu().pc))
+        }
+        ("BACKTRACE" | "BT", []) => Ok(ctx
+            .cpu()
+            .backtrace()
+            .lines()
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join(" | ")),
+        _ => Err(format!("Bad Command: {}", line.trim())),
+    }
+}
+
+// Function to serve one client until it disconnects
+fn serve_client(ctx: &Mutex<EmuContext>, stream: TcpStream) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let result = match ctx.lock() {
+            Ok(mut ctx_lock) => handle_command(&mut ctx_lock, &line),
+            Err(_) => Err(String::from("Emulation thread crashed")),
+        };
+        match result {
+            Ok(reply) if reply.is_empty() => writeln!(writer, "OK")?,
+            Ok(reply) => writeln!(writer, "OK {}", reply)?,
+            Err(e) => writeln!(writer, "ERR {}", e)?,
+        }
+    }
+    Ok(())
+}
+
+// Function to read commands from stdin so breaks can be resumed without a remote client
+pub fn spawn_console(ctx: Arc<Mutex<EmuContext>>) {
+    info!("Debug console on stdin: c to continue, s to step, bt for a backtrace");
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let result = match ctx.lock() {
+                Ok(mut ctx_lock) => handle_command(&mut ctx_lock, &line),
+                Err(_) => break,
+            };
+            match result {
+                Ok(reply) if reply.is_empty() => println!("OK"),
+                Ok(reply) => println!("OK {}", reply),
+                Err(e) => println!("ERR {}", e),
+            }
+        }
+    });
+}
+
+// Function to start the server on localhost, each client gets its own thread
+pub fn spawn_remote_server(ctx: Arc<Mutex<EmuContext>>, port: u16) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    info!("Remote access listening on 127.0.0.1:{}", port);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let ctx = Arc::clone(&ctx);
+                    thread::spawn(move || {
+                        if let Err(e) = serve_client(&ctx, stream) {
+                            warn!("Remote client dropped: {}", e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Remote connection failed: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdw::bus::Bus;
+    use crate::hdw::cart::Cartridge;
+
+    #[test]
+    fn reads_writes_and_resets() {
+        let mut ctx = EmuContext::new(Bus::new(Cartridge::new()));
+
+        assert_eq!(
+            handle_command(&mut ctx, "WRITE C000 12 ab"),
+            Ok(String::new())
+        );
+        assert_eq!(
+            handle_command(&mut ctx, "read 0xC000 3"),
+            Ok(String::from("12 AB 00"))
+        );
+        assert_eq!(
+            handle_command(&mut ctx, "READ $C001"),
+            Ok(String::from("AB"))
+        );
+        assert_eq!(handle_command(&mut ctx, "WRITE SCY 07"), Ok(String::new()));
+        assert_eq!(handle_command(&mut ctx, "READ SCY"), Ok(String::from("07")));
+
+        ctx.frames = 5;
+        assert_eq!(handle_command(&mut ctx, "RESET"), Ok(String::new()));
+        assert_eq!(handle_command(&mut ctx, "FRAME"), Ok(String::from("0")));
+
+        assert!(handle_command(&mut ctx, "WRITE C000 100").is_err());
+        assert!(handle_command(&mut ctx, "WRITE C000").is_err());
+        assert!(handle_command(&mut ctx, "READ nowhere").is_err());
+        assert!(handle_command(&mut ctx, "JUMP 0150").is_err());
+    }
+
+    #[test]
+    fn pauses_steps_and_continues() {
+        let mut ctx = EmuContext::new(Bus::new(Cartridge::new()));
+        // NOPs in WRAM
+        ctx.cpu_mut().pc = 0xC000;
+
+        assert!(handle_command(&mut ctx, "STEP").is_err());
+        assert_eq!(handle_command(&mut ctx, "PAUSE"), Ok(String::from("C000")));
+        assert_eq!(handle_command(&mut ctx, "s"), Ok(String::from("C001")));
+        assert_eq!(handle_command(&mut ctx, "step"), Ok(String::from("C002")));
+        assert_eq!(handle_command(&mut ctx, "BT"), Ok(String::from("#0  C002")));
+        assert_eq!(handle_command(&mut ctx, "c"), Ok(String::new()));
+        assert!(!ctx.is_paused());
+    }
+}
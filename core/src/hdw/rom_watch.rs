@@ -0,0 +1,96 @@
+/*
+
+    ROM File Watcher (--auto-reload)
+
+    Polls the loaded ROM's modified time so a rebuilt homebrew ROM is reloaded straight away
+    A change is only reported once the time has held still for a poll, so a ROM that is
+    still being written by the linker isn't loaded half finished
+
+*/
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub struct RomWatcher {
+    path: PathBuf,
+    interval: Duration,
+    modified: Option<SystemTime>,
+    pending: bool,
+    last_poll: Instant,
+}
+
+impl RomWatcher {
+    // Constructor
+    pub fn new(path: &str, interval: Duration) -> Self {
+        let path = PathBuf::from(path);
+        RomWatcher {
+            modified: fs::metadata(&path).and_then(|meta| meta.modified()).ok(),
+            path,
+            interval,
+            pending: false,
+            last_poll: Instant::now(),
+        }
+    }
+
+    // Method to check if the ROM changed and has finished changing (polls at most once an interval)
+    pub fn changed(&mut self) -> bool {
+        if self.last_poll.elapsed() < self.interval {
+            return false;
+        }
+        self.last_poll = Instant::now();
+
+        let modified = fs::metadata(&self.path)
+            .and_then(|meta| meta.modified())
+            .ok();
+        if modified != self.modified {
+            // Missing (mid rebuild) or still being written -> wait for it to settle
+            self.modified = modified;
+            self.pending = modified.is_some();
+            return false;
+        }
+
+        let changed = self.pending;
+        self.pending = false;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    #[test]
+    fn reports_a_change_once_it_settles() {
+        let dir = std::env::temp_dir().join(format!("gb_watch_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("game.gb");
+        fs::write(&path, [0]).unwrap();
+
+        let mut watcher = RomWatcher::new(&path.to_string_lossy(), Duration::ZERO);
+        assert!(!watcher.changed());
+
+        let touch = |seconds| {
+            let file = File::options().write(true).open(&path).unwrap();
+            file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+                .unwrap();
+        };
+
+        // Changed, then held still for a poll
+        touch(1000);
+        assert!(!watcher.changed());
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        // Still being written -> keeps waiting
+        touch(2000);
+        assert!(!watcher.changed());
+        touch(3000);
+        assert!(!watcher.changed());
+        assert!(watcher.changed());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
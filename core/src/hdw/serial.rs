@@ -0,0 +1,131 @@
+/*
+
+    Serial Port (Link Cable)
+
+    0xFF01 : SB - Byte being shifted out (and in)
+    0xFF02 : SC - Bit 7 transfer start/busy, bit 0 clock select (1 = internal)
+
+    With the internal clock a transfer shifts 8 bits at 8192 Hz (128 M-cycles a bit) then clears
+    SC bit 7 and requests the serial interrupt, with no cable attached 0xFF is shifted in
+    An external clock transfer never finishes since there's nothing on the other end
+
+    Every byte sent is captured so test ROMs that print over serial (blargg) can be read
+    The emu loop drains the capture with take_output
+
+*/
+use crate::hdw::state::{Savestate, StateReader, StateWriter};
+
+const CYCLES_PER_BYTE: u16 = 8 * 128;
+
+pub struct Serial {
+    sb: u8,
+    sc: u8,
+    cycles_left: u16,
+    output: Vec<u8>,
+}
+
+impl Serial {
+    // Constructor
+    pub fn new() -> Self {
+        Serial {
+            sb: 0,
+            sc: 0,
+            cycles_left: 0,
+            output: Vec::new(),
+        }
+    }
+
+    // Method to read SB or SC
+    pub fn read(&self, address: u16) -> u8 {
+        match address {
+            0xFF01 => self.sb,
+            _ => self.sc | 0x7E,
+        }
+    }
+
+    // Method to write SB or SC, starting a transfer captures the byte
+    pub fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0xFF01 => self.sb = value,
+            _ => {
+                self.sc = value & 0x81;
+                if self.sc == 0x81 {
+                    self.output.push(self.sb);
+                    self.cycles_left = CYCLES_PER_BYTE;
+                }
+            }
+        }
+    }
+
+    // Method to advance by some M-cycles, returns true if the serial interrupt was requested
+    pub fn tick(&mut self, m_cycles: u8) -> bool {
+        if self.cycles_left == 0 {
+            return false;
+        }
+
+        self.cycles_left = self.cycles_left.saturating_sub(m_cycles as u16);
+        if self.cycles_left == 0 {
+            // Nothing connected -> all 1s shifted in
+            self.sb = 0xFF;
+            self.sc &= 0x7F;
+            return true;
+        }
+        false
+    }
+
+    // Method to take the bytes sent since the last call
+    pub fn take_output(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.output)
+    }
+}
+
+impl Default for Serial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Captured output has already been handed out so it isn't saved
+impl Savestate for Serial {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.section(b"SERL");
+        writer.write_u8(self.sb);
+        writer.write_u8(self.sc);
+        writer.write_u16(self.cycles_left);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        reader.section(b"SERL")?;
+        self.sb = reader.read_u8()?;
+        self.sc = reader.read_u8()? & 0x81;
+        self.cycles_left = reader.read_u16()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_clock_transfer() {
+        let mut serial = Serial::new();
+        serial.write(0xFF01, b'P');
+        serial.write(0xFF02, 0x81);
+        assert_eq!(serial.read(0xFF02), 0xFF);
+
+        // Done after 8 bits at 128 M-cycles each
+        for _ in 0..CYCLES_PER_BYTE - 1 {
+            assert!(!serial.tick(1));
+        }
+        assert!(serial.tick(1));
+        assert_eq!((serial.read(0xFF01), serial.read(0xFF02)), (0xFF, 0x7F));
+        assert_eq!(serial.take_output(), b"P");
+        assert!(serial.take_output().is_empty());
+
+        // External clock waits forever
+        serial.write(0xFF02, 0x80);
+        assert!(!serial.tick(255));
+        assert!(serial.take_output().is_empty());
+    }
+}
@@ -0,0 +1,233 @@
+/*
+
+    LiveSplit Auto Splitter (--livesplit HOST:PORT SPLITS_FILE)
+
+    Watches memory once a frame and sends starttimer/split/reset to a LiveSplit Server
+
+    Splits file, one condition per line, '#' starts a comment:
+        start D35E == 01        <- timer starts when this becomes true
+        split wLevel >= 02      <- splits fire in file order, one at a time
+        split wLevel >= 03
+        reset D35E == 00        <- checked all the time, back to the first split
+
+    Addresses take hex, IO register names or .sym labels, values are hex bytes
+    Operators: == != < > <= >=
+    Conditions fire on the frame they become true so a value that stays true doesn't repeat
+    Only the next split is watched, so splits passed together (a level jump from 1 to 3)
+    fire one per frame as each becomes next
+
+*/
+use std::fs;
+
+use crate::hdw::debug::parse_address;
+use crate::hdw::symbols::SymbolTable;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Compare {
+    Equal,
+    NotEqual,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum SplitAction {
+    Start,
+    Split,
+    Reset,
+}
+
+struct Condition {
+    action: SplitAction,
+    address: u16,
+    compare: Compare,
+    value: u8,
+    was_true: bool,
+}
+
+pub struct AutoSplitter {
+    conditions: Vec<Condition>,
+    next_split: usize,
+    running: bool,
+}
+
+impl Condition {
+    // Function to check the condition, true only on the frame it becomes true
+    fn rising(&mut self, read: &impl Fn(u16) -> u8) -> bool {
+        let value = read(self.address);
+        let is_true = match self.compare {
+            Compare::Equal => value == self.value,
+            Compare::NotEqual => value != self.value,
+            Compare::Less => value < self.value,
+            Compare::Greater => value > self.value,
+            Compare::LessEqual => value <= self.value,
+            Compare::GreaterEqual => value >= self.value,
+        };
+        let rising = is_true && !self.was_true;
+        self.was_true = is_true;
+        rising
+    }
+}
+
+impl AutoSplitter {
+    // Function to parse a splits file's contents
+    pub fn parse(contents: &str, symbols: &SymbolTable) -> Result<AutoSplitter, String> {
+        let mut conditions = Vec::new();
+        for (line_num, line) in contents.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let bad_line = || format!("Bad Split on line {}: {}", line_num + 1, line);
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let [action, address, compare, value] = words.as_slice() else {
+                return Err(bad_line());
+            };
+            let action = match action.to_ascii_lowercase().as_str() {
+                "start" => SplitAction::Start,
+                "split" => SplitAction::Split,
+                "reset" => SplitAction::Reset,
+                _ => return Err(bad_line()),
+            };
+            let compare = match *compare {
+                "==" => Compare::Equal,
+                "!=" => Compare::NotEqual,
+                "<" => Compare::Less,
+                ">" => Compare::Greater,
+                "<=" => Compare::LessEqual,
+                ">=" => Compare::GreaterEqual,
+                _ => return Err(bad_line()),
+            };
+            let value =
+                u8::from_str_radix(value.trim_start_matches("0x"), 16).map_err(|_| bad_line())?;
+
+            conditions.push(Condition {
+                action,
+                address: parse_address(address, symbols)?,
+                compare,
+                value,
+                was_true: false,
+            });
+        }
+
+        Ok(AutoSplitter {
+            conditions,
+            next_split: 0,
+            running: false,
+        })
+    }
+
+    // Function to load a splits file from disk
+    pub fn load(file_path: &str, symbols: &SymbolTable) -> Result<AutoSplitter, String> {
+        let contents = fs::read_to_string(file_path)
+            .map_err(|e| format!("Failed to open: {}. Error: {}", file_path, e))?;
+        AutoSplitter::parse(&contents, symbols)
+    }
+
+    // Function to go back to the first split with no edges seen
+    fn restart_splits(&mut self) {
+        self.next_split = 0;
+        for condition in &mut self.conditions {
+            if condition.action == SplitAction::Split {
+                condition.was_true = false;
+            }
+        }
+    }
+
+    // Function to check the conditions after a frame, returns the LiveSplit command to send
+    pub fn update(&mut self, read: impl Fn(u16) -> u8) -> Option<&'static str> {
+        // Every condition is checked each frame so edges aren't missed while another is active
+        let mut command = None;
+        let mut split = 0;
+        for condition in &mut self.conditions {
+            match condition.action {
+                SplitAction::Reset => {
+                    if condition.rising(&read) && self.running {
+                        command = Some("reset");
+                    }
+                }
+                SplitAction::Start => {
+                    if condition.rising(&read) && !self.running {
+                        command = Some("starttimer");
+                    }
+                }
+                SplitAction::Split => {
+                    // Later splits keep their edge until they are next
+                    if self.running
+                        && split == self.next_split
+                        && command.is_none()
+                        && condition.rising(&read)
+                    {
+                        command = Some("split");
+                    }
+                    split += 1;
+                }
+            }
+        }
+
+        match command {
+            Some("reset") => {
+                self.running = false;
+                self.restart_splits();
+            }
+            Some("starttimer") => {
+                self.running = true;
+                self.restart_splits();
+            }
+            Some(_) => {
+                self.next_split += 1;
+                // Last split ends the run
+                if self.next_split == split {
+                    self.running = false;
+                }
+            }
+            None => {}
+        }
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn splits_in_order_on_rising_edges() {
+        let splits =
+            "start C000 == 01\nsplit C001 >= 02 # level 2\nsplit C001 >= 03\nreset C000 == 00\n";
+        let mut splitter = AutoSplitter::parse(splits, &SymbolTable::new()).unwrap();
+        let memory = [Cell::new(0u8), Cell::new(0u8)];
+        let read = |address: u16| memory[(address - 0xC000) as usize].get();
+
+        // Nothing until started
+        memory[1].set(2);
+        assert_eq!(splitter.update(read), None);
+        memory[1].set(0);
+        assert_eq!(splitter.update(read), None);
+
+        memory[0].set(1);
+        assert_eq!(splitter.update(read), Some("starttimer"));
+        assert_eq!(splitter.update(read), None);
+
+        // Level 3 straight away splits level 2 then level 3 on the next frame
+        memory[1].set(3);
+        assert_eq!(splitter.update(read), Some("split"));
+        assert_eq!(splitter.update(read), Some("split"));
+        assert_eq!(splitter.update(read), None);
+
+        // Restart then reset
+        memory[0].set(0);
+        assert_eq!(splitter.update(read), None);
+        memory[0].set(1);
+        assert_eq!(splitter.update(read), Some("starttimer"));
+        memory[0].set(0);
+        assert_eq!(splitter.update(read), Some("reset"));
+
+        assert!(AutoSplitter::parse("split C000 ~ 01", &SymbolTable::new()).is_err());
+        assert!(AutoSplitter::parse("pause C000 == 01", &SymbolTable::new()).is_err());
+    }
+}
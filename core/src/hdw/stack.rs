@@ -0,0 +1,37 @@
+use crate::hdw::cpu::CPU;
+
+pub fn stack_push(cpu: &mut CPU, value: u8) {
+    // Decrement Stack Pointer
+    cpu.sp = cpu.sp.wrapping_sub(1);
+    let cart_ram = cpu.bus.cart.ram_mapped();
+    cpu.call_stack.check_push(cpu.sp, cpu.pc, cart_ram);
+    // Write to the new top of the stack
+    cpu.bus.write_byte(cpu.sp, value);
+}
+
+pub fn stack_push16(cpu: &mut CPU, value: u16) {
+    // Push high byte
+    stack_push(cpu, (value >> 8) as u8);
+    // Push low byte
+    stack_push(cpu, (value & 0xFF) as u8);
+}
+
+pub fn stack_pop(cpu: &mut CPU) -> u8 {
+    // Grab Original Address
+    let address = cpu.sp;
+
+    // Increment SP
+    cpu.sp = cpu.sp.wrapping_add(1);
+
+    // Read the old top of the stack
+    cpu.bus.read_byte(address)
+}
+
+pub fn stack_pop16(cpu: &mut CPU) -> u16 {
+    // Pop Low and High Bytes
+    let low: u16 = stack_pop(cpu) as u16;
+    let high: u16 = stack_pop(cpu) as u16;
+
+    // Implicit Return Combined Bytes
+    (high << 8) | low
+}
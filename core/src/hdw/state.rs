@@ -0,0 +1,393 @@
+/*
+
+    Save State Codec
+
+    One serialization path shared by save states, rewind and netplay checksums
+
+    Layout (all little endian):
+        "GBST"          : Magic
+        u16             : Format version, bumped whenever any section changes
+        u16             : ROM global checksum so a state can't be loaded into the wrong game
+        sections...     : 4 byte tag followed by that module's fields in a fixed order
+
+    Each hardware module implements Savestate next to its own fields
+    New hardware (PPU, APU, DMA) adds a section and bumps STATE_VERSION
+
+    Files on disk (save states, battery saves) go through write_file_atomic so a crash
+    mid-write leaves the old file instead of a truncated one
+    They sit next to the ROM: game.sav for the battery save, game*.state for save states
+
+*/
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const STATE_MAGIC: [u8; 4] = *b"GBST";
+pub const STATE_VERSION: u16 = 6;
+
+// Trait for anything that carries emulation state
+pub trait Savestate {
+    fn save_state(&self, writer: &mut StateWriter);
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String>;
+}
+
+#[derive(Default)]
+pub struct StateWriter {
+    data: Vec<u8>,
+}
+
+impl StateWriter {
+    // Constructor
+    pub fn new() -> Self {
+        StateWriter { data: Vec::new() }
+    }
+
+    // Method to start a tagged section
+    pub fn section(&mut self, tag: &[u8; 4]) {
+        self.data.extend_from_slice(tag);
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.data.push(value as u8);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    // Method to write a length prefixed byte block
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.data
+            .extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        self.data.extend_from_slice(bytes);
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+pub struct StateReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> StateReader<'a> {
+    // Constructor
+    pub fn new(data: &'a [u8]) -> Self {
+        StateReader { data, pos: 0 }
+    }
+
+    // Method to take the next count bytes
+    fn take(&mut self, count: usize) -> Result<&'a [u8], String> {
+        let end = self.pos + count;
+        if end > self.data.len() {
+            return Err(format!("Save State Truncated at byte {}", self.pos));
+        }
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    // Method to check a section tag
+    pub fn section(&mut self, tag: &[u8; 4]) -> Result<(), String> {
+        let found = self.take(4)?;
+        if found != tag {
+            return Err(format!(
+                "Bad Save State Section: expected {} found {}",
+                String::from_utf8_lossy(tag),
+                String::from_utf8_lossy(found)
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, String> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, String> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    // Method to read a length prefixed byte block into a buffer of the same size
+    pub fn read_bytes_into(&mut self, buffer: &mut [u8]) -> Result<(), String> {
+        let mut length = [0; 4];
+        length.copy_from_slice(self.take(4)?);
+        let length = u32::from_le_bytes(length) as usize;
+        if length != buffer.len() {
+            return Err(format!(
+                "Save State Size Mismatch: expected {} bytes found {}",
+                buffer.len(),
+                length
+            ));
+        }
+        buffer.copy_from_slice(self.take(length)?);
+        Ok(())
+    }
+
+    // Method to check the whole state was used
+    pub fn finish(self) -> Result<(), String> {
+        if self.pos != self.data.len() {
+            return Err(format!(
+                "Save State has {} trailing bytes",
+                self.data.len() - self.pos
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Function to write the file header
+pub fn write_header(writer: &mut StateWriter, rom_checksum: u16) {
+    writer.section(&STATE_MAGIC);
+    writer.write_u16(STATE_VERSION);
+    writer.write_u16(rom_checksum);
+}
+
+// Function to check the file header against this build and the loaded ROM
+pub fn read_header(reader: &mut StateReader, rom_checksum: u16) -> Result<(), String> {
+    reader
+        .section(&STATE_MAGIC)
+        .map_err(|_| String::from("Not a Save State"))?;
+
+    let version = reader.read_u16()?;
+    if version != STATE_VERSION {
+        return Err(format!(
+            "Unsupported Save State Version: {} (expected {})",
+            version, STATE_VERSION
+        ));
+    }
+
+    let checksum = reader.read_u16()?;
+    if checksum != rom_checksum {
+        return Err(format!(
+            "Save State is for a different ROM (checksum {:04X}, loaded {:04X})",
+            checksum, rom_checksum
+        ));
+    }
+    Ok(())
+}
+
+// Function to hash a serialized state (FNV-1a) for netplay/desync checks
+pub fn state_checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xCBF2_9CE4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3)
+    })
+}
+
+// Function to replace a file without ever leaving it half written
+// Writes a temp file next to it, syncs it and renames it over the original
+pub fn write_file_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let written = File::create(&temp_path).and_then(|mut file| {
+        file.write_all(data)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|_| fs::rename(&temp_path, path)) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("Failed to write: {}. Error: {}", path.display(), e));
+    }
+
+    // Sync the directory so the rename itself survives power loss (not possible on Windows)
+    #[cfg(unix)]
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        File::open(dir)
+            .and_then(|dir| dir.sync_all())
+            .map_err(|e| format!("Failed to sync: {}. Error: {}", dir.display(), e))?;
+    }
+    Ok(())
+}
+
+// Battery save and save states found next to a ROM, with their modified times
+#[derive(Default)]
+pub struct SaveFiles {
+    pub battery: Option<(PathBuf, SystemTime)>,
+    pub states: Vec<(PathBuf, SystemTime)>,
+}
+
+// Function to find the saves for a ROM without loading them
+pub fn find_save_files(rom_path: &Path) -> SaveFiles {
+    let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    let mut saves = SaveFiles::default();
+
+    let battery = rom_path.with_extension("sav");
+    saves.battery = modified(&battery).map(|time| (battery, time));
+
+    let stem = rom_path.file_stem().unwrap_or_default().to_string_lossy();
+    let dir = rom_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    if let Ok(entries) = fs::read_dir(dir) {
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if name.starts_with(stem.as_ref()) && name.ends_with(".state") {
+                if let Some(time) = modified(&path) {
+                    saves.states.push((path, time));
+                }
+            }
+        }
+    }
+    saves.states.sort();
+    saves
+}
+
+// Function to format a file time as "YYYY-MM-DD HH:MM UTC"
+pub fn format_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let (days, of_day) = ((seconds / 86400) as i64, seconds % 86400);
+
+    // Days since 1970 to a civil date (Howard Hinnant's algorithm)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        of_day / 3600,
+        of_day % 3600 / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdw::bus::Bus;
+    use crate::hdw::cart::Cartridge;
+    use crate::hdw::cpu::CPU;
+
+    fn save(cpu: &CPU) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        write_header(&mut writer, 0x1234);
+        cpu.save_state(&mut writer);
+        writer.finish()
+    }
+
+    fn load(cpu: &mut CPU, data: &[u8]) -> Result<(), String> {
+        let mut reader = StateReader::new(data);
+        read_header(&mut reader, 0x1234)?;
+        cpu.load_state(&mut reader)?;
+        reader.finish()
+    }
+
+    #[test]
+    fn round_trips_cpu_and_memory() {
+        let mut cpu = CPU::new(Bus::new(Cartridge::new()));
+        cpu.registers.set_bc(0xBEEF);
+        cpu.pc = 0xC123;
+        cpu.sp = 0xDFF0;
+        cpu.master_enabled = true;
+        cpu.cycles = 123_456;
+        cpu.bus.write_byte(0xC000, 0x42);
+        cpu.bus.write_byte(0xFF80, 0x99);
+        cpu.bus.write_byte(0xFF42, 0x10);
+        cpu.bus.ie_register = 0x05;
+        let data = save(&cpu);
+
+        let mut restored = CPU::new(Bus::new(Cartridge::new()));
+        load(&mut restored, &data).unwrap();
+        assert_eq!(save(&restored), data);
+        assert_eq!(restored.registers.get_bc(), 0xBEEF);
+        assert_eq!(restored.bus.peek_byte(0xC000), 0x42);
+        assert_eq!(restored.bus.peek_byte(0xFF80), 0x99);
+        assert_eq!(state_checksum(&save(&restored)), state_checksum(&data));
+    }
+
+    #[test]
+    fn rejects_bad_states() {
+        let mut cpu = CPU::new(Bus::new(Cartridge::new()));
+        let data = save(&cpu);
+
+        // Wrong ROM
+        let mut reader = StateReader::new(&data);
+        assert!(read_header(&mut reader, 0x4321).is_err());
+
+        // Other version
+        let mut other = data.clone();
+        other[4] = other[4].wrapping_add(1);
+        assert!(load(&mut cpu, &other).is_err());
+
+        // Truncated and padded
+        assert!(load(&mut cpu, &data[..data.len() - 1]).is_err());
+        let mut padded = data.clone();
+        padded.push(0);
+        assert!(load(&mut cpu, &padded).is_err());
+        assert!(load(&mut cpu, b"nope").is_err());
+    }
+
+    #[test]
+    fn atomic_write_replaces_file() {
+        let dir = std::env::temp_dir().join(format!("gb_state_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("slot1.state");
+
+        write_file_atomic(&path, b"first").unwrap();
+        write_file_atomic(&path, b"second").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert!(!dir.join("slot1.state.tmp").exists());
+
+        // A failed write doesn't leave a temp file behind
+        assert!(write_file_atomic(&dir.join("missing").join("slot"), b"x").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn finds_saves_next_to_the_rom() {
+        let dir = std::env::temp_dir().join(format!("gb_saves_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let rom = dir.join("game.gb");
+        assert!(find_save_files(&rom).battery.is_none());
+
+        for name in ["game.sav", "game.state", "game.2.state", "other.state"] {
+            fs::write(dir.join(name), b"x").unwrap();
+        }
+        let saves = find_save_files(&rom);
+        assert_eq!(saves.battery.unwrap().0, dir.join("game.sav"));
+        let states: Vec<PathBuf> = saves.states.into_iter().map(|(path, _)| path).collect();
+        assert_eq!(states, [dir.join("game.2.state"), dir.join("game.state")]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn formats_timestamps_in_utc() {
+        let time = UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        assert_eq!(format_timestamp(time), "2023-11-14 22:13 UTC");
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01 00:00 UTC");
+    }
+}
@@ -0,0 +1,170 @@
+/*
+
+    RGBDS Symbol Files (.sym)
+
+    Each line is "BANK:ADDR Label" in hex, ';' starts a comment
+        00:0150 Main
+        01:4000 Main::Loop
+
+    Loaded from next to the ROM so the trace log and debugger can show and accept labels
+    Banks go past FF on big ROMs, RAM labels (8000+) are looked up by address alone and the
+    first one listed wins
+
+*/
+use std::collections::HashMap;
+use std::fs;
+
+pub struct SymbolTable {
+    labels: HashMap<(u16, u16), String>,
+    ram_labels: HashMap<u16, String>,
+    addresses: HashMap<String, u16>,
+}
+
+impl SymbolTable {
+    // Constructor
+    pub fn new() -> Self {
+        SymbolTable {
+            labels: HashMap::new(),
+            ram_labels: HashMap::new(),
+            addresses: HashMap::new(),
+        }
+    }
+
+    // Function to load a symbol file from disk
+    pub fn load(file_path: &str) -> Result<SymbolTable, String> {
+        let contents = fs::read_to_string(file_path)
+            .map_err(|e| format!("Failed to open: {}. Error: {}", file_path, e))?;
+
+        let mut symbols = SymbolTable::new();
+        for (line_num, line) in contents.lines().enumerate() {
+            // Strip Comments
+            let line = line.split(';').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            // Split into BANK:ADDR and Label
+            let (location, label) = line.split_once(char::is_whitespace).ok_or(format!(
+                "Bad Symbol on line {}: {}",
+                line_num + 1,
+                line
+            ))?;
+            let (bank, address) = location.split_once(':').ok_or(format!(
+                "Bad Symbol on line {}: {}",
+                line_num + 1,
+                line
+            ))?;
+
+            let bank = u16::from_str_radix(bank, 16)
+                .map_err(|_| format!("Bad Bank on line {}: {}", line_num + 1, bank))?;
+            let address = u16::from_str_radix(address, 16)
+                .map_err(|_| format!("Bad Address on line {}: {}", line_num + 1, address))?;
+
+            symbols.insert(bank, address, label.trim());
+        }
+
+        Ok(symbols)
+    }
+
+    // Function to add a label
+    pub fn insert(&mut self, bank: u16, address: u16, label: &str) {
+        // Keep the first label for an address so local labels don't hide their parent
+        if address < 0x8000 {
+            self.labels
+                .entry((bank, address))
+                .or_insert_with(|| label.to_string());
+        } else {
+            self.ram_labels
+                .entry(address)
+                .or_insert_with(|| label.to_string());
+        }
+        self.addresses.insert(label.to_string(), address);
+    }
+
+    // Function to find the label at an address
+    // rom_bank is the bank currently mapped at 0x4000 - 0x7FFF
+    pub fn label(&self, address: u16, rom_bank: u8) -> Option<&str> {
+        if (0x4000..0x8000).contains(&address) {
+            // Switchable ROM must match the mapped bank
+            self.labels
+                .get(&(rom_bank as u16, address))
+                .map(|s| s.as_str())
+        } else if address < 0x4000 {
+            self.labels.get(&(0, address)).map(|s| s.as_str())
+        } else {
+            // RAM labels may be listed under any bank
+            self.ram_labels.get(&address).map(|s| s.as_str())
+        }
+    }
+
+    // Function to find the address of a label
+    pub fn address(&self, label: &str) -> Option<u16> {
+        self.addresses.get(label).copied()
+    }
+
+    // Number of labels loaded
+    pub fn count(&self) -> usize {
+        self.addresses.len()
+    }
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to load symbol file contents through a temp file
+    fn load(contents: &str) -> Result<SymbolTable, String> {
+        let path = std::env::temp_dir().join(format!(
+            "gb_symbols_test_{}_{}.sym",
+            std::process::id(),
+            contents.len()
+        ));
+        fs::write(&path, contents).unwrap();
+        let symbols = SymbolTable::load(&path.to_string_lossy());
+        fs::remove_file(&path).unwrap();
+        symbols
+    }
+
+    #[test]
+    fn loads_labels_and_comments() {
+        let symbols = load("; comment\n00:0150 Main\n01:4000 Main::Loop ; inline\n\n").unwrap();
+        assert_eq!(symbols.count(), 2);
+        assert_eq!(symbols.label(0x0150, 1), Some("Main"));
+        assert_eq!(symbols.address("Main::Loop"), Some(0x4000));
+    }
+
+    #[test]
+    fn switchable_labels_need_the_mapped_bank() {
+        let symbols = load("01:4000 BankOne\n02:4000 BankTwo\n").unwrap();
+        assert_eq!(symbols.label(0x4000, 2), Some("BankTwo"));
+        assert_eq!(symbols.label(0x4000, 3), None);
+    }
+
+    #[test]
+    fn accepts_banks_past_ff() {
+        let symbols = load("1FF:4000 Late\n").unwrap();
+        assert_eq!(symbols.address("Late"), Some(0x4000));
+    }
+
+    #[test]
+    fn ram_labels_keep_the_first_listed() {
+        let symbols = load("00:C000 wFirst\n01:C000 wSecond\n00:C000 wThird\n").unwrap();
+        for _ in 0..8 {
+            assert_eq!(symbols.label(0xC000, 1), Some("wFirst"));
+        }
+        assert_eq!(symbols.address("wSecond"), Some(0xC000));
+    }
+
+    #[test]
+    fn rejects_bad_lines() {
+        assert!(load("0150 Main\n").is_err());
+        assert!(load("00:XYZ Main\n").is_err());
+        assert!(load("00:0150\n").is_err());
+    }
+}
@@ -0,0 +1,27 @@
+/*
+
+    Test Helpers (test-util feature)
+
+    Shared by the unit tests, the integration tests under tests/ and the benchmarks
+    Programs run from WRAM so nothing depends on a cartridge
+
+*/
+use crate::hdw::bus::Bus;
+use crate::hdw::cart::Cartridge;
+use crate::hdw::cpu::CPU;
+
+// Where programs are loaded and where the stack starts
+pub const START: u16 = 0xC000;
+pub const STACK: u16 = 0xDFFE;
+
+// Function to build a CPU with a program loaded at START and AF cleared
+pub fn cpu_with_program(program: &[u8]) -> CPU {
+    let mut cpu = CPU::new(Bus::new(Cartridge::new()));
+    for (i, byte) in program.iter().enumerate() {
+        cpu.bus.write_byte(START + i as u16, *byte);
+    }
+    cpu.pc = START;
+    cpu.sp = STACK;
+    cpu.registers.set_af(0);
+    cpu
+}
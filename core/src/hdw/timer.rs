@@ -0,0 +1,302 @@
+/*
+
+    Gameboy Timer
+
+    0xFF04 : DIV  - Upper 8 bits of a 16-bit counter that goes up every T-cycle, any write resets it
+    0xFF05 : TIMA - Counts up at the TAC rate, requests the timer interrupt on overflow
+    0xFF06 : TMA  - Loaded into TIMA after an overflow
+    0xFF07 : TAC  - Bit 2 enable, bits 0-1 select which counter bit clocks TIMA
+                    00 -> bit 9 (4096 Hz), 01 -> bit 3 (262144 Hz), 10 -> bit 5 (65536 Hz), 11 -> bit 7 (16384 Hz)
+
+    TIMA is clocked on the falling edge of (selected bit AND enable), so resetting DIV or
+    changing TAC while the bit is high clocks TIMA once (the "glitch" writes)
+
+    After an overflow TIMA reads 0x00 for one M-cycle, then TMA is loaded and the interrupt requested
+        - Writing TIMA during that cycle cancels the reload and the interrupt
+        - During the reload cycle TIMA writes are ignored and TMA writes go straight through to TIMA
+
+    DIV-APU: The falling edge of counter bit 12 (DIV bit 4) steps the APU frame sequencer
+
+*/
+use crate::hdw::state::{Savestate, StateReader, StateWriter};
+
+pub struct Timer {
+    counter: u16,
+    tima: u8,
+    tma: u8,
+    tac: u8,
+
+    // Overflow delay
+    reload_pending: bool,
+    reloading: bool,
+
+    // DIV-APU frame sequencer step (0-7)
+    pub frame_sequencer: u8,
+}
+
+impl Timer {
+    // Constructor
+    pub fn new() -> Self {
+        Timer {
+            counter: 0,
+            tima: 0,
+            tma: 0,
+            tac: 0,
+            reload_pending: false,
+            reloading: false,
+            frame_sequencer: 0,
+        }
+    }
+
+    // Function to get the counter bit TAC selects
+    fn tac_bit(&self) -> u16 {
+        match self.tac & 0x03 {
+            0 => 1 << 9,
+            1 => 1 << 3,
+            2 => 1 << 5,
+            _ => 1 << 7,
+        }
+    }
+
+    // Function to get the signal TIMA is clocked from
+    fn timer_signal(&self) -> bool {
+        self.tac & 0x04 != 0 && self.counter & self.tac_bit() != 0
+    }
+
+    // Function to increment TIMA, starting the reload delay on overflow
+    fn increment_tima(&mut self) {
+        let (tima, overflow) = self.tima.overflowing_add(1);
+        self.tima = tima;
+        if overflow {
+            self.reload_pending = true;
+        }
+    }
+
+    // Function to move the counter, clocking TIMA and DIV-APU on falling edges
+    fn set_counter(&mut self, counter: u16) {
+        let timer_before = self.timer_signal();
+        let apu_before = self.counter & (1 << 12) != 0;
+
+        self.counter = counter;
+
+        if timer_before && !self.timer_signal() {
+            self.increment_tima();
+        }
+        if apu_before && self.counter & (1 << 12) == 0 {
+            self.frame_sequencer = (self.frame_sequencer + 1) & 0x07;
+        }
+    }
+
+    // Method to advance by some M-cycles, returns true if the timer interrupt was requested
+    pub fn tick(&mut self, m_cycles: u8) -> bool {
+        let mut interrupt = false;
+
+        for _ in 0..m_cycles {
+            // The reload cycle only lasts one M-cycle
+            self.reloading = false;
+
+            // Finish an overflow from the last M-cycle
+            if self.reload_pending {
+                self.reload_pending = false;
+                self.reloading = true;
+                self.tima = self.tma;
+                interrupt = true;
+            }
+
+            self.set_counter(self.counter.wrapping_add(4));
+        }
+
+        interrupt
+    }
+
+    // Method to set DIV directly (boot state), the low counter bits start clear
+    pub fn set_div(&mut self, div: u8) {
+        self.counter = (div as u16) << 8;
+    }
+
+    // Method to read a timer register
+    pub fn read(&self, address: u16) -> u8 {
+        match address {
+            0xFF04 => (self.counter >> 8) as u8,
+            0xFF05 => self.tima,
+            0xFF06 => self.tma,
+            _ => self.tac | 0xF8,
+        }
+    }
+
+    // Method to write a timer register
+    pub fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0xFF04 => self.set_counter(0),
+            0xFF05 => {
+                // Ignored while reloading, cancels a pending reload
+                if !self.reloading {
+                    self.tima = value;
+                    self.reload_pending = false;
+                }
+            }
+            0xFF06 => {
+                self.tma = value;
+                if self.reloading {
+                    self.tima = value;
+                }
+            }
+            _ => {
+                let before = self.timer_signal();
+                self.tac = value & 0x07;
+                if before && !self.timer_signal() {
+                    self.increment_tima();
+                }
+            }
+        }
+    }
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Savestate for Timer {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.section(b"TIMR");
+        writer.write_u16(self.counter);
+        writer.write_u8(self.tima);
+        writer.write_u8(self.tma);
+        writer.write_u8(self.tac);
+        writer.write_bool(self.reload_pending);
+        writer.write_bool(self.reloading);
+        writer.write_u8(self.frame_sequencer);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        reader.section(b"TIMR")?;
+        self.counter = reader.read_u16()?;
+        self.tima = reader.read_u8()?;
+        self.tma = reader.read_u8()?;
+        self.tac = reader.read_u8()? & 0x07;
+        self.reload_pending = reader.read_bool()?;
+        self.reloading = reader.read_bool()?;
+        self.frame_sequencer = reader.read_u8()? & 0x07;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to run more M-cycles than fit in one tick
+    fn run(timer: &mut Timer, m_cycles: u32) {
+        for _ in 0..m_cycles {
+            timer.tick(1);
+        }
+    }
+
+    // Timer at 262144 Hz (TIMA every 4 M-cycles)
+    fn fast_timer() -> Timer {
+        let mut timer = Timer::new();
+        timer.write(0xFF07, 0x05);
+        timer
+    }
+
+    #[test]
+    fn div_and_tima_rates() {
+        let mut timer = Timer::new();
+        timer.tick(63);
+        assert_eq!(timer.read(0xFF04), 0);
+        timer.tick(1);
+        assert_eq!(timer.read(0xFF04), 1);
+
+        // 4096 Hz -> every 256 M-cycles
+        let mut timer = Timer::new();
+        timer.write(0xFF07, 0x04);
+        run(&mut timer, 255);
+        assert_eq!(timer.read(0xFF05), 0);
+        timer.tick(1);
+        assert_eq!(timer.read(0xFF05), 1);
+
+        // Disabled doesn't count
+        let mut timer = Timer::new();
+        timer.write(0xFF07, 0x01);
+        timer.tick(100);
+        assert_eq!(timer.read(0xFF05), 0);
+        assert_eq!(timer.read(0xFF07), 0xF9);
+    }
+
+    #[test]
+    fn overflow_reloads_one_cycle_late() {
+        let mut timer = fast_timer();
+        timer.write(0xFF06, 0xAB);
+        timer.write(0xFF05, 0xFF);
+
+        // Overflow -> reads 0 for a cycle with no interrupt
+        assert!(!timer.tick(4));
+        assert_eq!(timer.read(0xFF05), 0x00);
+
+        // Then TMA is loaded and the interrupt requested
+        assert!(timer.tick(1));
+        assert_eq!(timer.read(0xFF05), 0xAB);
+    }
+
+    #[test]
+    fn tima_write_cancels_reload() {
+        let mut timer = fast_timer();
+        timer.write(0xFF06, 0xAB);
+        timer.write(0xFF05, 0xFF);
+        timer.tick(4);
+
+        timer.write(0xFF05, 0x12);
+        assert!(!timer.tick(1));
+        assert_eq!(timer.read(0xFF05), 0x12);
+    }
+
+    #[test]
+    fn reload_cycle_writes() {
+        let mut timer = fast_timer();
+        timer.write(0xFF05, 0xFF);
+        timer.tick(5);
+
+        // TIMA writes are ignored, TMA writes land in TIMA too
+        timer.write(0xFF05, 0x12);
+        assert_eq!(timer.read(0xFF05), 0x00);
+        timer.write(0xFF06, 0x34);
+        assert_eq!(timer.read(0xFF05), 0x34);
+    }
+
+    #[test]
+    fn glitch_writes_clock_tima() {
+        // DIV reset while the selected bit is high
+        let mut timer = fast_timer();
+        timer.tick(2);
+        timer.write(0xFF04, 0x00);
+        assert_eq!((timer.read(0xFF04), timer.read(0xFF05)), (0, 1));
+
+        // Disabling while the selected bit is high
+        let mut timer = fast_timer();
+        timer.tick(2);
+        timer.write(0xFF07, 0x01);
+        assert_eq!(timer.read(0xFF05), 1);
+
+        // Bit low -> no glitch
+        let mut timer = fast_timer();
+        timer.tick(1);
+        timer.write(0xFF04, 0x00);
+        assert_eq!(timer.read(0xFF05), 0);
+    }
+
+    #[test]
+    fn div_apu_steps_on_bit_12_falling() {
+        let mut timer = Timer::new();
+        run(&mut timer, 2047);
+        assert_eq!(timer.frame_sequencer, 0);
+        timer.tick(1);
+        assert_eq!(timer.frame_sequencer, 1);
+
+        // DIV reset with bit 12 high steps it early
+        run(&mut timer, 1024);
+        timer.write(0xFF04, 0x00);
+        assert_eq!(timer.frame_sequencer, 2);
+    }
+}
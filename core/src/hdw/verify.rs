@@ -0,0 +1,212 @@
+/*
+
+    ROM Collection Verifier
+
+    emu verify <dir> checks every .gb/.gbc file in a directory and prints a table of
+    header checksum, global checksum, size and mapper support problems
+
+    Files are split across one thread per core since reading a large collection is mostly IO
+
+*/
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use crate::hdw::cart::{check_rom, RomCheck};
+
+// Function to check a single ROM file
+fn verify_file(path: &Path) -> Result<RomCheck, String> {
+    let data =
+        fs::read(path).map_err(|e| format!("Failed to open: {}. Error: {}", path.display(), e))?;
+    check_rom(&data)
+}
+
+// Function to list the problems with a checked ROM
+fn problems(check: &RomCheck) -> Vec<&'static str> {
+    let mut problems = Vec::new();
+    if !check.header_checksum_ok {
+        problems.push("header checksum");
+    }
+    if !check.global_checksum_ok {
+        problems.push("global checksum");
+    }
+    if check.actual_size != check.expected_size {
+        problems.push("size");
+    }
+    if !check.supported {
+        problems.push("unsupported mapper");
+    }
+    problems
+}
+
+fn pass_fail(ok: bool) -> &'static str {
+    if ok {
+        "OK"
+    } else {
+        "FAIL"
+    }
+}
+
+// Each file checked and what it found
+type CheckResults = Vec<(PathBuf, Result<RomCheck, String>)>;
+
+// Function to check every .gb/.gbc file in a directory, results are in file name order
+fn check_dir(dir: &str) -> Result<CheckResults, String> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to open: {}. Error: {}", dir, e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| {
+                    extension.eq_ignore_ascii_case("gb") || extension.eq_ignore_ascii_case("gbc")
+                })
+        })
+        .collect();
+    paths.sort();
+
+    // Check in parallel, chunks keep the results in order
+    let threads = thread::available_parallelism().map_or(1, |count| count.get());
+    let chunk_size = paths.len().div_ceil(threads).max(1);
+    let results: Vec<Result<RomCheck, String>> = thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(|path| verify_file(path)).collect()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle: thread::ScopedJoinHandle<Vec<_>>| {
+                handle.join().expect("ROM verify thread panicked")
+            })
+            .collect()
+    });
+
+    Ok(paths.into_iter().zip(results).collect())
+}
+
+// Function to verify every ROM in a directory, returns the number of ROMs with problems
+pub fn verify_dir(dir: &str) -> Result<usize, String> {
+    let results = check_dir(dir)?;
+
+    // Table
+    println!(
+        "{:<32} {:<16} {:<28} {:<6} {:<6} {:<6} Status",
+        "File", "Title", "Mapper", "Header", "Global", "Size"
+    );
+    let mut bad = 0;
+    for (path, result) in &results {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        match result {
+            Ok(check) => {
+                let problems = problems(check);
+                if !problems.is_empty() {
+                    bad += 1;
+                }
+                println!(
+                    "{:<32} {:<16} {:<28} {:<6} {:<6} {:<6} {}",
+                    file_name,
+                    // CGB flag bytes at the end of short titles aren't printable
+                    check
+                        .title
+                        .chars()
+                        .filter(|c| c.is_ascii_graphic() || *c == ' ')
+                        .collect::<String>(),
+                    check.mapper,
+                    pass_fail(check.header_checksum_ok),
+                    pass_fail(check.global_checksum_ok),
+                    pass_fail(check.actual_size == check.expected_size),
+                    if problems.is_empty() {
+                        String::from("OK")
+                    } else {
+                        problems.join(", ")
+                    }
+                );
+            }
+            Err(e) => {
+                bad += 1;
+                println!("{:<32} {}", file_name, e);
+            }
+        }
+    }
+    println!("\n{} ROMs checked, {} with problems", results.len(), bad);
+
+    Ok(bad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to build a ROM with both checksums filled in
+    fn rom(cart_type: u8, rom_size: u8) -> Vec<u8> {
+        let mut rom = vec![0; 0x8000];
+        rom[0x0147] = cart_type;
+        rom[0x0148] = rom_size;
+        rom[0x014D] = rom[0x0134..=0x014C].iter().fold(0u8, |checksum, &byte| {
+            checksum.wrapping_sub(byte).wrapping_sub(1)
+        });
+        let global = rom
+            .iter()
+            .fold(0u16, |sum, &byte| sum.wrapping_add(byte as u16));
+        rom[0x014E..=0x014F].copy_from_slice(&global.to_be_bytes());
+        rom
+    }
+
+    #[test]
+    fn problems_flag_checksums_size_and_mapper() {
+        assert!(problems(&check_rom(&rom(0x00, 0x00)).unwrap()).is_empty());
+
+        let mut bad_checksum = rom(0x00, 0x00);
+        bad_checksum[0x014D] ^= 0xFF;
+        assert_eq!(
+            problems(&check_rom(&bad_checksum).unwrap()),
+            ["header checksum", "global checksum"]
+        );
+
+        // Header says 64KB
+        assert_eq!(problems(&check_rom(&rom(0x00, 0x01)).unwrap()), ["size"]);
+
+        // MBC3
+        assert_eq!(
+            problems(&check_rom(&rom(0x13, 0x00)).unwrap()),
+            ["unsupported mapper"]
+        );
+    }
+
+    #[test]
+    fn verifies_a_directory_in_order() {
+        let dir = std::env::temp_dir().join(format!("gb_verify_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut bad_checksum = rom(0x01, 0x00);
+        bad_checksum[0x014D] ^= 0xFF;
+        fs::write(dir.join("a_good.gb"), rom(0x01, 0x00)).unwrap();
+        fs::write(dir.join("b_bad_checksum.GB"), bad_checksum).unwrap();
+        fs::write(dir.join("c_truncated.gbc"), [0; 0x100]).unwrap();
+        fs::write(dir.join("d_mbc3.gb"), rom(0x13, 0x00)).unwrap();
+        fs::write(dir.join("notes.txt"), "not a ROM").unwrap();
+
+        let dir_name = dir.to_string_lossy();
+        let results = check_dir(&dir_name).unwrap();
+        let names: Vec<_> = results
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "a_good.gb",
+                "b_bad_checksum.GB",
+                "c_truncated.gbc",
+                "d_mbc3.gb"
+            ]
+        );
+        assert!(results[0]
+            .1
+            .as_ref()
+            .is_ok_and(|check| problems(check).is_empty()));
+        assert!(results[2].1.as_ref().is_err());
+
+        assert_eq!(verify_dir(&dir_name), Ok(3));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
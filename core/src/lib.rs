@@ -0,0 +1,12 @@
+/*
+    Emulation core (gameboy-core) - no frontend dependencies so other projects can embed it
+    The GameBoy binary at the workspace root is the command line front end
+
+    Emulator is the supported entry point, hdw exposes the hardware for tests and tools
+*/
+mod emulator;
+pub mod hdw;
+
+pub use emulator::Emulator;
+pub use hdw::joypad::Button;
+pub use hdw::model::Model;
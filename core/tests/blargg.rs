@@ -0,0 +1,47 @@
+/*
+
+    blargg cpu_instrs ROMs run through the Emulator facade
+
+    Each ROM prints its name then "Passed" or "Failed" over serial
+
+*/
+use gameboy::Emulator;
+
+// Long enough for the slowest ROM (02-interrupts) with room to spare
+const MAX_FRAMES: u64 = 2000;
+
+fn run_rom(name: &str) -> String {
+    let path = format!("{}/../src/roms/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let mut emulator = Emulator::load_rom(&path).unwrap();
+
+    let mut output = String::new();
+    while emulator.frame_count() < MAX_FRAMES {
+        emulator.frame().unwrap();
+        output.push_str(&String::from_utf8_lossy(&emulator.take_serial_output()));
+        if output.contains("Passed") || output.contains("Failed") {
+            break;
+        }
+    }
+    output
+}
+
+#[test]
+fn cpu_instrs_individual_roms_pass() {
+    let roms = [
+        "01-special.gb",
+        "02-interrupts.gb",
+        "03-op sp,hl.gb",
+        "04-op r,imm.gb",
+        "05-op rp.gb",
+        "06-ld r,r.gb",
+        "07-jr,jp,call,ret,rst.gb",
+        "08-misc instrs.gb",
+        "09-op r,r.gb",
+        "10-bit ops.gb",
+        "11-op a,(hl).gb",
+    ];
+    for rom in roms {
+        let output = run_rom(rom);
+        assert!(output.contains("Passed"), "{}: {:?}", rom, output);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::*;
+
+// Function to run a single A, B ALU op and return (A, F)
+fn alu(opcode: u8, a: u8, b: u8, f: u8) -> (u8, u8) {
+    let mut cpu = cpu_with_program(&[opcode]);
+    cpu.registers.a = a;
+    cpu.registers.b = b;
+    set_flags(&mut cpu, f);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.pc, START + 1);
+    (cpu.registers.a, flags(&cpu))
+}
+
+#[test]
+fn add() {
+    assert_eq!(alu(0x80, 0x3A, 0xC6, 0), (0x00, Z | H | C));
+    assert_eq!(alu(0x80, 0x0F, 0x01, 0), (0x10, H));
+    assert_eq!(alu(0x80, 0xF0, 0x20, N), (0x10, C));
+    assert_eq!(alu(0x80, 0x12, 0x34, Z | N | H | C), (0x46, 0));
+}
+
+#[test]
+fn adc() {
+    assert_eq!(alu(0x88, 0xE1, 0x0F, C), (0xF1, H));
+    assert_eq!(alu(0x88, 0xE1, 0x3B, C), (0x1D, C));
+    assert_eq!(alu(0x88, 0xE1, 0x1E, C), (0x00, Z | H | C));
+    assert_eq!(alu(0x88, 0x0F, 0x00, C), (0x10, H));
+    assert_eq!(alu(0x88, 0xFF, 0x00, C), (0x00, Z | H | C));
+}
+
+#[test]
+fn sub() {
+    assert_eq!(alu(0x90, 0x3E, 0x3E, 0), (0x00, Z | N));
+    assert_eq!(alu(0x90, 0x3E, 0x0F, 0), (0x2F, N | H));
+    assert_eq!(alu(0x90, 0x3E, 0x40, 0), (0xFE, N | C));
+    assert_eq!(alu(0x97, 0x55, 0x00, C), (0x00, Z | N)); // SUB A, A
+}
+
+#[test]
+fn sbc() {
+    assert_eq!(alu(0x98, 0x3B, 0x2A, C), (0x10, N));
+    assert_eq!(alu(0x98, 0x3B, 0x4F, C), (0xEB, N | H | C));
+    assert_eq!(alu(0x98, 0x10, 0x0F, C), (0x00, Z | N | H));
+    assert_eq!(alu(0x98, 0x00, 0xFF, C), (0x00, Z | N | H | C));
+    assert_eq!(alu(0x9F, 0x42, 0x00, C), (0xFF, N | H | C)); // SBC A, A
+}
+
+#[test]
+fn and_or_xor() {
+    assert_eq!(alu(0xA0, 0x5A, 0x3F, C), (0x1A, H));
+    assert_eq!(alu(0xA0, 0x5A, 0x00, 0), (0x00, Z | H));
+    assert_eq!(alu(0xB0, 0x5A, 0x03, C), (0x5B, 0));
+    assert_eq!(alu(0xB0, 0x00, 0x00, 0), (0x00, Z));
+    assert_eq!(alu(0xA8, 0xFF, 0x0F, C), (0xF0, 0));
+    assert_eq!(alu(0xAF, 0x42, 0x00, N | H | C), (0x00, Z)); // XOR A, A
+}
+
+#[test]
+fn cp() {
+    // A is left alone
+    assert_eq!(alu(0xB8, 0x3C, 0x2F, 0), (0x3C, N | H));
+    assert_eq!(alu(0xB8, 0x3C, 0x3C, 0), (0x3C, Z | N));
+    assert_eq!(alu(0xB8, 0x3C, 0x40, 0), (0x3C, N | C));
+}
+
+#[test]
+fn immediate_and_hl_operands() {
+    // ADD A, n8
+    let mut cpu = cpu_with_program(&[0xC6, 0x05]);
+    cpu.registers.a = 0x10;
+    run(&mut cpu, 1);
+    assert_eq!((cpu.registers.a, cpu.pc), (0x15, START + 2));
+
+    // SUB A, (HL)
+    let mut cpu = cpu_with_program(&[0x96]);
+    cpu.bus.write_byte(0xD000, 0x01);
+    cpu.registers.set_hl(0xD000);
+    cpu.registers.a = 0x10;
+    run(&mut cpu, 1);
+    assert_eq!((cpu.registers.a, flags(&cpu)), (0x0F, N | H));
+
+    // CP A, n8
+    let mut cpu = cpu_with_program(&[0xFE, 0x10]);
+    cpu.registers.a = 0x10;
+    run(&mut cpu, 1);
+    assert_eq!((flags(&cpu), cpu.pc), (Z | N, START + 2));
+}
+
+#[test]
+fn inc_dec_8bit() {
+    // INC B keeps carry
+    let mut cpu = cpu_with_program(&[0x04]);
+    cpu.registers.b = 0xFF;
+    set_flags(&mut cpu, C | N);
+    run(&mut cpu, 1);
+    assert_eq!((cpu.registers.b, flags(&cpu)), (0x00, Z | H | C));
+
+    // DEC C sets N and half borrow
+    let mut cpu = cpu_with_program(&[0x0D]);
+    cpu.registers.c = 0x10;
+    run(&mut cpu, 1);
+    assert_eq!((cpu.registers.c, flags(&cpu)), (0x0F, N | H));
+
+    // DEC A to zero
+    let mut cpu = cpu_with_program(&[0x3D]);
+    cpu.registers.a = 0x01;
+    run(&mut cpu, 1);
+    assert_eq!((cpu.registers.a, flags(&cpu)), (0x00, Z | N));
+
+    // INC (HL)
+    let mut cpu = cpu_with_program(&[0x34]);
+    cpu.bus.write_byte(0xD000, 0x0F);
+    cpu.registers.set_hl(0xD000);
+    run(&mut cpu, 1);
+    assert_eq!((cpu.bus.read_byte(0xD000), flags(&cpu)), (0x10, H));
+
+    // DEC (HL)
+    let mut cpu = cpu_with_program(&[0x35]);
+    cpu.bus.write_byte(0xD000, 0x00);
+    cpu.registers.set_hl(0xD000);
+    run(&mut cpu, 1);
+    assert_eq!((cpu.bus.read_byte(0xD000), flags(&cpu)), (0xFF, N | H));
+}
+
+#[test]
+fn inc_dec_16bit() {
+    // INC BC / DEC DE / INC SP leave flags alone
+    let mut cpu = cpu_with_program(&[0x03, 0x1B, 0x33]);
+    cpu.registers.set_bc(0xFFFF);
+    cpu.registers.set_de(0x0000);
+    set_flags(&mut cpu, Z | C);
+    run(&mut cpu, 3);
+    assert_eq!(cpu.registers.get_bc(), 0x0000);
+    assert_eq!(cpu.registers.get_de(), 0xFFFF);
+    assert_eq!(cpu.sp, STACK + 1);
+    assert_eq!(flags(&cpu), Z | C);
+}
+
+#[test]
+fn add_hl() {
+    // ADD HL, BC -> Z untouched, H from bit 11, C from bit 15
+    let mut cpu = cpu_with_program(&[0x09]);
+    cpu.registers.set_hl(0x8A23);
+    cpu.registers.set_bc(0x0605);
+    set_flags(&mut cpu, Z);
+    run(&mut cpu, 1);
+    assert_eq!((cpu.registers.get_hl(), flags(&cpu)), (0x9028, Z | H));
+
+    let mut cpu = cpu_with_program(&[0x29]);
+    cpu.registers.set_hl(0x8A23);
+    run(&mut cpu, 1);
+    assert_eq!((cpu.registers.get_hl(), flags(&cpu)), (0x1446, H | C));
+}
+
+#[test]
+fn sp_offsets() {
+    // ADD SP, e8 -> flags from the low byte
+    let mut cpu = cpu_with_program(&[0xE8, 0x02]);
+    cpu.sp = 0xFFF8;
+    set_flags(&mut cpu, Z | N);
+    run(&mut cpu, 1);
+    assert_eq!((cpu.sp, flags(&cpu), cpu.pc), (0xFFFA, 0, START + 2));
+
+    let mut cpu = cpu_with_program(&[0xE8, 0xFF]);
+    cpu.sp = 0x0001;
+    run(&mut cpu, 1);
+    assert_eq!((cpu.sp, flags(&cpu)), (0x0000, H | C));
+
+    // LD HL, SP+e8
+    let mut cpu = cpu_with_program(&[0xF8, 0x02]);
+    cpu.sp = 0xFFF8;
+    run(&mut cpu, 1);
+    assert_eq!(
+        (cpu.registers.get_hl(), flags(&cpu), cpu.sp),
+        (0xFFFA, 0, 0xFFF8)
+    );
+}
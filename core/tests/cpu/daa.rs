@@ -0,0 +1,68 @@
+use crate::*;
+
+// Function to run ADD/SUB A, B then DAA and return (A, F)
+fn bcd(opcode: u8, a: u8, b: u8) -> (u8, u8) {
+    let mut cpu = cpu_with_program(&[opcode, 0x27]);
+    cpu.registers.a = a;
+    cpu.registers.b = b;
+    run(&mut cpu, 2);
+    (cpu.registers.a, flags(&cpu))
+}
+
+#[test]
+fn daa_after_add() {
+    assert_eq!(bcd(0x80, 0x45, 0x38), (0x83, 0));
+    assert_eq!(bcd(0x80, 0x09, 0x01), (0x10, 0));
+    assert_eq!(bcd(0x80, 0x99, 0x01), (0x00, Z | C));
+    assert_eq!(bcd(0x80, 0x50, 0x50), (0x00, Z | C));
+    assert_eq!(bcd(0x80, 0x08, 0x08), (0x16, 0));
+    assert_eq!(bcd(0x80, 0x90, 0x90), (0x80, C));
+}
+
+#[test]
+fn daa_after_sub() {
+    assert_eq!(bcd(0x90, 0x83, 0x38), (0x45, N));
+    assert_eq!(bcd(0x90, 0x10, 0x01), (0x09, N));
+    assert_eq!(bcd(0x90, 0x00, 0x01), (0x99, N | C));
+    assert_eq!(bcd(0x90, 0x20, 0x20), (0x00, Z | N));
+}
+
+#[test]
+fn daa_keeps_n_and_clears_h() {
+    // DAA on its own with H set adjusts by 6
+    let mut cpu = cpu_with_program(&[0x27]);
+    cpu.registers.a = 0x00;
+    set_flags(&mut cpu, H);
+    run(&mut cpu, 1);
+    assert_eq!((cpu.registers.a, flags(&cpu)), (0x06, 0));
+
+    let mut cpu = cpu_with_program(&[0x27]);
+    cpu.registers.a = 0x00;
+    set_flags(&mut cpu, N | H);
+    run(&mut cpu, 1);
+    assert_eq!((cpu.registers.a, flags(&cpu)), (0xFA, N));
+}
+
+#[test]
+fn cpl_scf_ccf() {
+    // CPL sets N and H
+    let mut cpu = cpu_with_program(&[0x2F]);
+    cpu.registers.a = 0x35;
+    set_flags(&mut cpu, Z | C);
+    run(&mut cpu, 1);
+    assert_eq!((cpu.registers.a, flags(&cpu)), (0xCA, Z | N | H | C));
+
+    // SCF clears N and H
+    let mut cpu = cpu_with_program(&[0x37]);
+    set_flags(&mut cpu, Z | N | H);
+    run(&mut cpu, 1);
+    assert_eq!(flags(&cpu), Z | C);
+
+    // CCF clears N and H and flips C
+    let mut cpu = cpu_with_program(&[0x3F, 0x3F]);
+    set_flags(&mut cpu, N | H | C);
+    run(&mut cpu, 1);
+    assert_eq!(flags(&cpu), 0);
+    run(&mut cpu, 1);
+    assert_eq!(flags(&cpu), C);
+}
@@ -0,0 +1,144 @@
+use crate::*;
+
+#[test]
+fn jp() {
+    // JP a16
+    let mut cpu = cpu_with_program(&[0xC3, 0x34, 0x12]);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.pc, 0x1234);
+
+    // JP HL
+    let mut cpu = cpu_with_program(&[0xE9]);
+    cpu.registers.set_hl(0xC123);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.pc, 0xC123);
+}
+
+#[test]
+fn jp_conditions() {
+    // (opcode, flags that make it jump, flags that don't)
+    for (opcode, taken, not_taken) in [(0xC2, 0, Z), (0xCA, Z, 0), (0xD2, 0, C), (0xDA, C, 0)] {
+        let mut cpu = cpu_with_program(&[opcode, 0x00, 0xD0]);
+        set_flags(&mut cpu, taken);
+        run(&mut cpu, 1);
+        assert_eq!(cpu.pc, 0xD000, "{:02X} taken", opcode);
+
+        let mut cpu = cpu_with_program(&[opcode, 0x00, 0xD0]);
+        set_flags(&mut cpu, not_taken);
+        run(&mut cpu, 1);
+        assert_eq!(cpu.pc, START + 3, "{:02X} not taken", opcode);
+    }
+}
+
+#[test]
+fn jr() {
+    // JR +2 skips over two bytes
+    let mut cpu = cpu_with_program(&[0x18, 0x02]);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.pc, START + 4);
+
+    // JR -2 loops on itself
+    let mut cpu = cpu_with_program(&[0x18, 0xFE]);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.pc, START);
+
+    // Conditions
+    for (opcode, taken, not_taken) in [(0x20, 0, Z), (0x28, Z, 0), (0x30, 0, C), (0x38, C, 0)] {
+        let mut cpu = cpu_with_program(&[opcode, 0x10]);
+        set_flags(&mut cpu, taken);
+        run(&mut cpu, 1);
+        assert_eq!(cpu.pc, START + 0x12, "{:02X} taken", opcode);
+
+        let mut cpu = cpu_with_program(&[opcode, 0x10]);
+        set_flags(&mut cpu, not_taken);
+        run(&mut cpu, 1);
+        assert_eq!(cpu.pc, START + 2, "{:02X} not taken", opcode);
+    }
+}
+
+#[test]
+fn call_and_ret() {
+    // CALL C010 -> RET comes back after the CALL
+    let mut program = vec![0xCD, 0x10, 0xC0];
+    program.resize(0x10, 0x00);
+    program.push(0xC9);
+    let mut cpu = cpu_with_program(&program);
+
+    run(&mut cpu, 1);
+    assert_eq!(cpu.pc, 0xC010);
+    assert_eq!(cpu.sp, STACK - 2);
+    assert_eq!(cpu.bus.read_byte(STACK - 1), 0xC0);
+    assert_eq!(cpu.bus.read_byte(STACK - 2), 0x03);
+
+    run(&mut cpu, 1);
+    assert_eq!(cpu.pc, START + 3);
+    assert_eq!(cpu.sp, STACK);
+}
+
+#[test]
+fn call_and_ret_conditions() {
+    for (opcode, taken, not_taken) in [(0xC4, 0, Z), (0xCC, Z, 0), (0xD4, 0, C), (0xDC, C, 0)] {
+        let mut cpu = cpu_with_program(&[opcode, 0x00, 0xD0]);
+        set_flags(&mut cpu, taken);
+        run(&mut cpu, 1);
+        assert_eq!(
+            (cpu.pc, cpu.sp),
+            (0xD000, STACK - 2),
+            "{:02X} taken",
+            opcode
+        );
+
+        let mut cpu = cpu_with_program(&[opcode, 0x00, 0xD0]);
+        set_flags(&mut cpu, not_taken);
+        run(&mut cpu, 1);
+        assert_eq!(
+            (cpu.pc, cpu.sp),
+            (START + 3, STACK),
+            "{:02X} not taken",
+            opcode
+        );
+    }
+
+    for (opcode, taken, not_taken) in [(0xC0, 0, Z), (0xC8, Z, 0), (0xD0, 0, C), (0xD8, C, 0)] {
+        let mut cpu = cpu_with_program(&[opcode]);
+        cpu.sp = STACK - 2;
+        cpu.bus.write_byte(STACK - 2, 0x00);
+        cpu.bus.write_byte(STACK - 1, 0xD0);
+        set_flags(&mut cpu, taken);
+        run(&mut cpu, 1);
+        assert_eq!((cpu.pc, cpu.sp), (0xD000, STACK), "{:02X} taken", opcode);
+
+        let mut cpu = cpu_with_program(&[opcode]);
+        set_flags(&mut cpu, not_taken);
+        run(&mut cpu, 1);
+        assert_eq!(
+            (cpu.pc, cpu.sp),
+            (START + 1, STACK),
+            "{:02X} not taken",
+            opcode
+        );
+    }
+}
+
+#[test]
+fn rst() {
+    for (opcode, vector) in [(0xC7, 0x00), (0xCF, 0x08), (0xEF, 0x28), (0xFF, 0x38)] {
+        let mut cpu = cpu_with_program(&[opcode]);
+        run(&mut cpu, 1);
+        assert_eq!(cpu.pc, vector, "{:02X}", opcode);
+        assert_eq!(cpu.sp, STACK - 2);
+        assert_eq!(cpu.bus.read_byte(STACK - 2), 0x01);
+        assert_eq!(cpu.bus.read_byte(STACK - 1), 0xC0);
+    }
+}
+
+#[test]
+fn reti_enables_interrupts() {
+    let mut cpu = cpu_with_program(&[0xD9]);
+    cpu.sp = STACK - 2;
+    cpu.bus.write_byte(STACK - 2, 0x00);
+    cpu.bus.write_byte(STACK - 1, 0xD0);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.pc, 0xD000);
+    assert!(cpu.master_enabled);
+}
@@ -0,0 +1,145 @@
+use crate::*;
+
+#[test]
+fn ld_register_to_register() {
+    // LD B, A / LD C, B / LD D, C / LD E, D / LD H, E / LD L, H / LD A, L
+    let mut cpu = cpu_with_program(&[0x47, 0x48, 0x51, 0x5A, 0x63, 0x6C, 0x7D]);
+    cpu.registers.a = 0x5A;
+    run(&mut cpu, 6);
+    let r = &cpu.registers;
+    assert_eq!([r.b, r.c, r.d, r.e, r.h, r.l], [0x5A; 6]);
+
+    cpu.registers.l = 0x77;
+    run(&mut cpu, 1);
+    assert_eq!(cpu.registers.a, 0x77);
+    assert_eq!(cpu.pc, START + 7);
+}
+
+#[test]
+fn ld_immediates() {
+    // LD B, n8 / LD A, n8 / LD BC, n16 / LD SP, n16 / LD HL, n16
+    let mut cpu = cpu_with_program(&[
+        0x06, 0x12, 0x3E, 0x34, 0x01, 0x78, 0x56, 0x31, 0xF0, 0xDF, 0x21, 0x00, 0xD0,
+    ]);
+    run(&mut cpu, 5);
+    assert_eq!(cpu.registers.b, 0x56);
+    assert_eq!(cpu.registers.a, 0x34);
+    assert_eq!(cpu.registers.c, 0x78);
+    assert_eq!(cpu.sp, 0xDFF0);
+    assert_eq!(cpu.registers.get_hl(), 0xD000);
+    assert_eq!(cpu.pc, START + 13);
+}
+
+#[test]
+fn ld_hl_memory() {
+    // LD (HL), n8 / LD B, (HL) / LD (HL), B
+    let mut cpu = cpu_with_program(&[0x36, 0x99, 0x46, 0x2C, 0x70]);
+    cpu.registers.set_hl(0xD000);
+    run(&mut cpu, 4);
+    assert_eq!(cpu.bus.read_byte(0xD000), 0x99);
+    assert_eq!(cpu.registers.b, 0x99);
+    assert_eq!(cpu.bus.read_byte(0xD001), 0x99);
+}
+
+#[test]
+fn ld_indirect_pairs() {
+    // LD (BC), A / LD A, (DE)
+    let mut cpu = cpu_with_program(&[0x02, 0x1A]);
+    cpu.registers.a = 0x42;
+    cpu.registers.set_bc(0xD010);
+    cpu.registers.set_de(0xD020);
+    cpu.bus.write_byte(0xD020, 0x24);
+    run(&mut cpu, 2);
+    assert_eq!(cpu.bus.read_byte(0xD010), 0x42);
+    assert_eq!(cpu.registers.a, 0x24);
+}
+
+#[test]
+fn ld_hl_increment_decrement() {
+    // LD (HL+), A / LD (HL-), A / LD A, (HL+) / LD A, (HL-)
+    let mut cpu = cpu_with_program(&[0x22, 0x32, 0x2A, 0x3A]);
+    cpu.registers.a = 0x11;
+    cpu.registers.set_hl(0xD000);
+
+    run(&mut cpu, 1);
+    assert_eq!(cpu.bus.read_byte(0xD000), 0x11);
+    assert_eq!(cpu.registers.get_hl(), 0xD001);
+
+    run(&mut cpu, 1);
+    assert_eq!(cpu.bus.read_byte(0xD001), 0x11);
+    assert_eq!(cpu.registers.get_hl(), 0xD000);
+
+    cpu.bus.write_byte(0xD000, 0x22);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.registers.a, 0x22);
+    assert_eq!(cpu.registers.get_hl(), 0xD001);
+
+    run(&mut cpu, 1);
+    assert_eq!(cpu.registers.a, 0x11);
+    assert_eq!(cpu.registers.get_hl(), 0xD000);
+}
+
+#[test]
+fn ldh_and_absolute() {
+    // LDH (a8), A / LDH A, (a8) / LD (C), A / LD A, (C)
+    let mut cpu = cpu_with_program(&[0xE0, 0x80, 0xF0, 0x81, 0xE2, 0xF2]);
+    cpu.registers.a = 0x5C;
+    cpu.registers.c = 0x82;
+    cpu.bus.write_byte(0xFF81, 0x7E);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.bus.read_byte(0xFF80), 0x5C);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.registers.a, 0x7E);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.bus.read_byte(0xFF82), 0x7E);
+    cpu.bus.write_byte(0xFF82, 0x01);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.registers.a, 0x01);
+    assert_eq!(cpu.pc, START + 6);
+
+    // LD (a16), A / LD A, (a16)
+    let mut cpu = cpu_with_program(&[0xEA, 0x00, 0xD1, 0xFA, 0x01, 0xD1]);
+    cpu.registers.a = 0x33;
+    cpu.bus.write_byte(0xD101, 0x44);
+    run(&mut cpu, 2);
+    assert_eq!(cpu.bus.read_byte(0xD100), 0x33);
+    assert_eq!(cpu.registers.a, 0x44);
+    assert_eq!(cpu.pc, START + 6);
+}
+
+#[test]
+fn ld_stack_pointer() {
+    // LD (a16), SP
+    let mut cpu = cpu_with_program(&[0x08, 0x00, 0xD0]);
+    cpu.sp = 0xBEEF;
+    run(&mut cpu, 1);
+    assert_eq!(cpu.bus.read_byte(0xD000), 0xEF);
+    assert_eq!(cpu.bus.read_byte(0xD001), 0xBE);
+    assert_eq!(cpu.pc, START + 3);
+
+    // LD SP, HL
+    let mut cpu = cpu_with_program(&[0xF9]);
+    cpu.registers.set_hl(0xD123);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.sp, 0xD123);
+}
+
+#[test]
+fn push_pop() {
+    // PUSH BC / POP DE
+    let mut cpu = cpu_with_program(&[0xC5, 0xD1]);
+    cpu.registers.set_bc(0x1234);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.sp, STACK - 2);
+    assert_eq!(cpu.bus.read_byte(STACK - 1), 0x12);
+    assert_eq!(cpu.bus.read_byte(STACK - 2), 0x34);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.registers.get_de(), 0x1234);
+    assert_eq!(cpu.sp, STACK);
+
+    // POP AF drops the low nibble of F
+    let mut cpu = cpu_with_program(&[0xC5, 0xF1]);
+    cpu.registers.set_bc(0x12FF);
+    run(&mut cpu, 2);
+    assert_eq!(cpu.registers.get_af(), 0x12F0);
+}
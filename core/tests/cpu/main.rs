@@ -0,0 +1,41 @@
+/*
+
+    CPU Instruction Tests
+
+    Each test writes a short program into WRAM, runs it a set number of steps and
+    checks registers, flags and memory. No cartridge is loaded so nothing here depends on a ROM
+
+*/
+mod alu;
+mod daa;
+mod fuzz;
+mod jumps;
+mod loads;
+mod rotates;
+
+use gameboy::hdw::cpu::CPU;
+pub use gameboy::hdw::test_util::{cpu_with_program, STACK, START};
+
+// Flag bits as they sit in F
+pub const Z: u8 = 0x80;
+pub const N: u8 = 0x40;
+pub const H: u8 = 0x20;
+pub const C: u8 = 0x10;
+
+// Function to run a number of instructions
+pub fn run(cpu: &mut CPU, steps: usize) {
+    for ticks in 0..steps {
+        cpu.step(ticks as u64).unwrap();
+    }
+}
+
+// Function to get the flags as they'd be stored in F
+pub fn flags(cpu: &CPU) -> u8 {
+    cpu.registers.get_af() as u8
+}
+
+// Function to set the flags from an F value
+pub fn set_flags(cpu: &mut CPU, f: u8) {
+    let a = cpu.registers.a;
+    cpu.registers.set_af((a as u16) << 8 | f as u16);
+}
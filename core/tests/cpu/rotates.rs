@@ -0,0 +1,84 @@
+use crate::*;
+
+// Function to run a CB op on B and return (B, F)
+fn cb(cb_opcode: u8, b: u8, f: u8) -> (u8, u8) {
+    let mut cpu = cpu_with_program(&[0xCB, cb_opcode]);
+    cpu.registers.b = b;
+    set_flags(&mut cpu, f);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.pc, START + 2);
+    (cpu.registers.b, flags(&cpu))
+}
+
+// Function to run an accumulator rotate and return (A, F)
+fn rotate_a(opcode: u8, a: u8, f: u8) -> (u8, u8) {
+    let mut cpu = cpu_with_program(&[opcode]);
+    cpu.registers.a = a;
+    set_flags(&mut cpu, f);
+    run(&mut cpu, 1);
+    (cpu.registers.a, flags(&cpu))
+}
+
+#[test]
+fn accumulator_rotates() {
+    // Z is always cleared
+    assert_eq!(rotate_a(0x07, 0x85, Z), (0x0B, C)); // RLCA
+    assert_eq!(rotate_a(0x07, 0x00, 0), (0x00, 0));
+    assert_eq!(rotate_a(0x0F, 0x3B, 0), (0x9D, C)); // RRCA
+    assert_eq!(rotate_a(0x17, 0x95, C), (0x2B, C)); // RLA
+    assert_eq!(rotate_a(0x17, 0x80, 0), (0x00, C));
+    assert_eq!(rotate_a(0x1F, 0x81, 0), (0x40, C)); // RRA
+    assert_eq!(rotate_a(0x1F, 0x00, C), (0x80, 0));
+}
+
+#[test]
+fn prefixed_rotates() {
+    assert_eq!(cb(0x00, 0x85, 0), (0x0B, C)); // RLC
+    assert_eq!(cb(0x00, 0x00, 0), (0x00, Z));
+    assert_eq!(cb(0x08, 0x01, 0), (0x80, C)); // RRC
+    assert_eq!(cb(0x10, 0x80, 0), (0x00, Z | C)); // RL
+    assert_eq!(cb(0x10, 0x11, C), (0x23, 0));
+    assert_eq!(cb(0x18, 0x01, 0), (0x00, Z | C)); // RR
+    assert_eq!(cb(0x18, 0x8A, C), (0xC5, 0));
+}
+
+#[test]
+fn prefixed_shifts() {
+    assert_eq!(cb(0x20, 0x80, 0), (0x00, Z | C)); // SLA
+    assert_eq!(cb(0x20, 0x41, C), (0x82, 0));
+    assert_eq!(cb(0x28, 0x8A, 0), (0xC5, 0)); // SRA keeps bit 7
+    assert_eq!(cb(0x28, 0x01, 0), (0x00, Z | C));
+    assert_eq!(cb(0x38, 0x8B, 0), (0x45, C)); // SRL
+    assert_eq!(cb(0x38, 0x01, 0), (0x00, Z | C));
+    assert_eq!(cb(0x30, 0xF1, C), (0x1F, 0)); // SWAP
+    assert_eq!(cb(0x30, 0x00, C), (0x00, Z));
+}
+
+#[test]
+fn bit_res_set() {
+    // BIT keeps C, sets H, Z is the inverse of the bit
+    assert_eq!(cb(0x40, 0x01, C), (0x01, H | C)); // BIT 0, B
+    assert_eq!(cb(0x78, 0x7F, 0), (0x7F, Z | H)); // BIT 7, B
+    assert_eq!(cb(0x80, 0xFF, C), (0xFE, C)); // RES 0, B
+    assert_eq!(cb(0xB8, 0xFF, 0), (0x7F, 0)); // RES 7, B
+    assert_eq!(cb(0xC0, 0x00, Z), (0x01, Z)); // SET 0, B
+    assert_eq!(cb(0xF8, 0x00, 0), (0x80, 0)); // SET 7, B
+}
+
+#[test]
+fn prefixed_hl_memory() {
+    // RLC (HL) / SET 3, (HL) / BIT 3, (HL) / RES 3, (HL)
+    let mut cpu = cpu_with_program(&[0xCB, 0x06, 0xCB, 0xDE, 0xCB, 0x5E, 0xCB, 0x9E]);
+    cpu.registers.set_hl(0xD000);
+    cpu.bus.write_byte(0xD000, 0x81);
+
+    run(&mut cpu, 1);
+    assert_eq!((cpu.bus.read_byte(0xD000), flags(&cpu)), (0x03, C));
+    run(&mut cpu, 1);
+    assert_eq!(cpu.bus.read_byte(0xD000), 0x0B);
+    run(&mut cpu, 1);
+    assert_eq!(flags(&cpu), H | C);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.bus.read_byte(0xD000), 0x03);
+    assert_eq!(cpu.pc, START + 8);
+}
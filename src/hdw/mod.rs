@@ -1,14 +0,0 @@
-/*
-    hdw mod file to allow files to be shared between eachother
-*/
-pub mod bus;
-pub mod cart;
-pub mod cpu;
-pub mod cpu_ops;
-pub mod cpu_util;
-pub mod emu;
-pub mod instructions;
-pub mod interrupts;
-pub mod ram;
-pub mod registers;
-pub mod stack;
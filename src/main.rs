@@ -1,6 +1,4 @@
-mod hdw;
-
-use crate::hdw::emu::emu_run;
+use gameboy::hdw::emu::emu_run;
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();